@@ -1,25 +1,67 @@
-use crate::{config::Config, terminal::ask_dialog};
+use crate::{
+    config::Config,
+    terminal::{ask_dialog, ask_select, is_terminal},
+};
 
 #[derive(Eq, PartialEq, Debug)]
 pub enum CompletionResult {
     Found,
     FoundSimilar(String),
+    Ambiguous(Vec<String>),
     Nothing,
 }
 
-pub fn autocomplete(word: &str, words_list: &[&str], config: &Config) -> Option<String> {
+/// Kind of the match between the input and a candidate, from the strongest to the weakest.
+#[derive(Eq, PartialEq, Ord, PartialOrd, Debug, Clone, Copy)]
+pub enum MatchKind {
+    /// Same name, but with different letter case.
+    CaseInsensitive,
+    Prefix,
+    Substring,
+    /// All characters of the input appear in the candidate in the same order.
+    Subsequence,
+    /// The candidate is a few edits away from the input.
+    Typo,
+}
+
+/// Score of a candidate. Lower is better.
+#[derive(Eq, PartialEq, Ord, PartialOrd, Debug, Clone, Copy)]
+pub struct MatchScore {
+    pub kind: MatchKind,
+    pub penalty: usize,
+}
+
+/// Completes the word using the list of known words.
+///
+/// Unambiguous suggestions are accepted automatically if `always_accept` is enabled, unless
+/// the completion is used by a `destructive` action. Ambiguous suggestions are always
+/// presented to the user as a list.
+pub fn autocomplete(
+    word: &str,
+    words_list: &[&str],
+    config: &Config,
+    destructive: bool,
+) -> Option<String> {
     let suggested = suggest_completion(word, words_list);
 
     match suggested {
         CompletionResult::Found => Some(word.to_string()),
         CompletionResult::FoundSimilar(name) => {
-            if config.autocomplete.always_accept {
+            if config.autocomplete.always_accept && !destructive {
                 return Some(name);
             }
             let answer =
                 ask_dialog(&format!("Did you mean '{name}'?"), true, false).unwrap_or(false);
             if answer { Some(name) } else { None }
         }
+        CompletionResult::Ambiguous(names) => {
+            if !is_terminal() {
+                return None;
+            }
+            let question = format!("'{word}' matches several projects. Which one did you mean?");
+            let index = ask_select(&question, &names, true).ok()?;
+            names.get(index).cloned()
+        }
         CompletionResult::Nothing => None,
     }
 }
@@ -29,13 +71,99 @@ pub fn suggest_completion(word: &str, words_list: &[&str]) -> CompletionResult {
         return CompletionResult::Found;
     }
 
-    let word_lowercase = word.to_lowercase();
-    if let Some(similar) = words_list
+    let mut scored: Vec<(MatchScore, &str)> = words_list
         .iter()
-        .find(|entry| entry.to_lowercase().starts_with(&word_lowercase))
-    {
-        CompletionResult::FoundSimilar(similar.to_string())
+        .filter_map(|entry| score_match(word, entry).map(|score| (score, *entry)))
+        .collect();
+    scored.sort_by_key(|(score, _)| *score);
+
+    let Some((best, _)) = scored.first().copied() else {
+        return CompletionResult::Nothing;
+    };
+
+    let mut candidates: Vec<String> = scored
+        .iter()
+        .take_while(|(score, _)| score.kind == best.kind)
+        .map(|(_, entry)| entry.to_string())
+        .collect();
+
+    if candidates.len() == 1 {
+        CompletionResult::FoundSimilar(candidates.remove(0))
     } else {
-        CompletionResult::Nothing
+        CompletionResult::Ambiguous(candidates)
+    }
+}
+
+/// Scores how well the candidate matches the input, ignoring letter case.
+/// Returns `None` if the candidate doesn't match at all.
+pub fn score_match(word: &str, candidate: &str) -> Option<MatchScore> {
+    let word = word.to_lowercase();
+    let candidate = candidate.to_lowercase();
+    if word.is_empty() {
+        return None;
+    }
+
+    let length_difference = candidate.chars().count().abs_diff(word.chars().count());
+    let score = |kind, penalty| Some(MatchScore { kind, penalty });
+
+    if candidate == word {
+        return score(MatchKind::CaseInsensitive, 0);
+    }
+
+    if candidate.starts_with(&word) {
+        return score(MatchKind::Prefix, length_difference);
+    }
+
+    if let Some(position) = candidate.find(&word) {
+        return score(MatchKind::Substring, position);
+    }
+
+    if let Some(gaps) = subsequence_gaps(&word, &candidate) {
+        return score(MatchKind::Subsequence, gaps);
+    }
+
+    let distance = edit_distance(&word, &candidate);
+    // Inputs shorter than three characters are a typo away from almost any short name.
+    let max_distance = word.chars().count() / 3;
+    if distance <= max_distance {
+        return score(MatchKind::Typo, distance);
     }
+
+    None
+}
+
+/// Returns the number of skipped characters if `word` is a subsequence of `candidate`.
+fn subsequence_gaps(word: &str, candidate: &str) -> Option<usize> {
+    let mut candidate_chars = candidate.chars();
+    let mut gaps = 0;
+
+    for ch in word.chars() {
+        loop {
+            let next = candidate_chars.next()?;
+            if next == ch {
+                break;
+            }
+            gaps += 1;
+        }
+    }
+
+    Some(gaps)
+}
+
+/// Calculates the Levenshtein distance between two strings.
+pub fn edit_distance(a: &str, b: &str) -> usize {
+    let b_chars: Vec<char> = b.chars().collect();
+    let mut previous: Vec<usize> = (0..=b_chars.len()).collect();
+    let mut current = vec![0; b_chars.len() + 1];
+
+    for (i, a_char) in a.chars().enumerate() {
+        current[0] = i + 1;
+        for (j, b_char) in b_chars.iter().enumerate() {
+            let substitution = previous[j] + usize::from(a_char != *b_char);
+            current[j + 1] = substitution.min(previous[j + 1] + 1).min(current[j] + 1);
+        }
+        std::mem::swap(&mut previous, &mut current);
+    }
+
+    previous[b_chars.len()]
 }
@@ -3,7 +3,7 @@ use indexmap::IndexMap;
 use std::{fs, path::Path};
 use thiserror::Error;
 
-use serde::{Deserialize, Serialize};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
 
 use crate::config::Config;
 
@@ -27,10 +27,27 @@ pub enum BackupError {
 
 #[derive(Serialize, Deserialize)]
 pub struct Backup {
+    #[serde(
+        serialize_with = "serialize_config",
+        deserialize_with = "deserialize_config"
+    )]
     pub config: Config,
     pub blueprints: IndexMap<String, String>,
 }
 
+/// Saves the configuration as it is written to the file, with OS sections and `extends`.
+fn serialize_config<S: Serializer>(config: &Config, serializer: S) -> Result<S::Ok, S::Error> {
+    config
+        .to_table()
+        .map_err(serde::ser::Error::custom)?
+        .serialize(serializer)
+}
+
+fn deserialize_config<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Config, D::Error> {
+    let value = toml::Value::deserialize(deserializer)?;
+    Config::from_value(value).map_err(serde::de::Error::custom)
+}
+
 pub fn load_backup(path: impl AsRef<Path>) -> Result<Backup, BackupError> {
     let content = fs::read_to_string(path).map_err(BackupError::FileSystemError)?;
     serde_json::from_str::<Backup>(&content)
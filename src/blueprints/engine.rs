@@ -52,6 +52,18 @@ impl BlueprintEngine {
             .map(|_| ())
     }
 
+    /// Calls a global function defined by the previously run code.
+    /// Returns `false` if there is no function with this name.
+    pub fn call_function(&self, name: &str) -> LuaResult<bool> {
+        match self.lua.globals().get::<LuaValue>(name)? {
+            LuaValue::Function(function) => {
+                function.call::<()>(())?;
+                Ok(true)
+            }
+            _ => Ok(false),
+        }
+    }
+
     pub fn current_dir(&self) -> &Path {
         &self.current_dir
     }
@@ -10,9 +10,15 @@ pub use blueprints::{
     BlueprintsRemoveArgs,
 };
 pub use commands::Commands;
-pub use config::{ConfigCommands, RecentArgs};
-pub use profiles::{ProfilesCommands, ProfilesGetArgs, ProfilesRemoveArgs, ProfilesSetArgs};
+pub use config::{
+    ConfigCommands, ConfigGetArgs, ConfigSetArgs, ConfigUnsetArgs, RecentArgs, ShowArgs,
+};
+pub use profiles::{
+    ProfilesCommands, ProfilesCopyArgs, ProfilesEditArgs, ProfilesGetArgs, ProfilesNewArgs,
+    ProfilesRemoveArgs, ProfilesRenameArgs, ProfilesSetArgs,
+};
 pub use projects::{
-    BackupArgs, CloneArgs, ImportArgs, ListArgs, NewArgs, OpenArgs, RemoveArgs, RenameArgs,
+    BackupArgs, CloneArgs, CompletionsArgs, CurrentArgs, ImportArgs, InfoArgs, InitArgs, ListArgs,
+    NewArgs, OpenArgs, PinArgs, RemoveArgs, RenameArgs, UnpinArgs,
 };
 pub use root::Cli;
@@ -1,4 +1,7 @@
 use clap::{Args, Subcommand};
+use clap_complete::ArgValueCandidates;
+
+use crate::completions::complete_blueprints;
 
 #[derive(Subcommand)]
 pub enum BlueprintsCommands {
@@ -30,17 +33,20 @@ pub struct BlueprintsNewArgs {
 #[derive(Args)]
 pub struct BlueprintsEditArgs {
     /// Name of blueprint to edit.
+    #[arg(add = ArgValueCandidates::new(complete_blueprints))]
     pub name: String,
 }
 
 #[derive(Args)]
 pub struct BlueprintsCheckArgs {
     /// Name of blueprint to check.
+    #[arg(add = ArgValueCandidates::new(complete_blueprints))]
     pub name: String,
 }
 
 #[derive(Args)]
 pub struct BlueprintsRemoveArgs {
     /// Name of blueprint to remove.
+    #[arg(add = ArgValueCandidates::new(complete_blueprints))]
     pub name: String,
 }
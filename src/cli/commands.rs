@@ -1,8 +1,9 @@
 use clap::Subcommand;
 
 use super::{
-    BackupArgs, BlueprintsCommands, CloneArgs, ConfigCommands, ImportArgs, ListArgs, NewArgs,
-    OpenArgs, ProfilesCommands, RemoveArgs, RenameArgs,
+    BackupArgs, BlueprintsCommands, CloneArgs, CompletionsArgs, ConfigCommands, CurrentArgs,
+    ImportArgs, InfoArgs, InitArgs, ListArgs, NewArgs, OpenArgs, PinArgs, ProfilesCommands,
+    RemoveArgs, RenameArgs, UnpinArgs,
 };
 
 #[derive(Subcommand)]
@@ -21,6 +22,18 @@ pub enum Commands {
     #[command(alias = "ls")]
     List(ListArgs),
 
+    /// Pin project to the top of the list.
+    Pin(PinArgs),
+
+    /// Unpin project.
+    Unpin(UnpinArgs),
+
+    /// Show information about project.
+    Info(InfoArgs),
+
+    /// Show the project of the current shell session or directory.
+    Current(CurrentArgs),
+
     /// Rename project.
     Rename(RenameArgs),
 
@@ -52,6 +65,12 @@ pub enum Commands {
     /// Import the configuration and blueprints from backup file. Will overwrite the current ones.
     Import(ImportArgs),
 
+    /// Print the shell integration script.
+    Init(InitArgs),
+
+    /// Print the completion script for the shell.
+    Completions(CompletionsArgs),
+
     /// Display the Zen of Kanri.
     Zen,
 }
@@ -13,6 +13,18 @@ pub enum ConfigCommands {
 
     /// Reset your configuration.
     Reset,
+
+    /// Show the effective configuration with all layers applied.
+    Show(ShowArgs),
+
+    /// Print a value, like `options.display_hidden`.
+    Get(ConfigGetArgs),
+
+    /// Change a value in the configuration file. Comments in the file are kept.
+    Set(ConfigSetArgs),
+
+    /// Remove a value from the configuration file, so its default is used.
+    Unset(ConfigUnsetArgs),
 }
 
 #[derive(Args)]
@@ -21,3 +33,32 @@ pub struct RecentArgs {
     #[arg(short, long, action = ArgAction::SetTrue)]
     pub clear: bool,
 }
+
+#[derive(Args)]
+pub struct ShowArgs {
+    /// Show where each value comes from.
+    #[arg(short, long, action = ArgAction::SetTrue)]
+    pub origin: bool,
+}
+
+#[derive(Args)]
+pub struct ConfigGetArgs {
+    /// Dotted key, like `options.display_hidden` or `profiles.default.editor`.
+    pub key: String,
+}
+
+#[derive(Args)]
+pub struct ConfigSetArgs {
+    /// Dotted key, like `autocomplete.always_accept` or `profiles.default.env.RUST_LOG`.
+    pub key: String,
+
+    /// New value. Values are parsed as TOML, and as plain strings if that fails.
+    #[arg(allow_hyphen_values = true)]
+    pub value: String,
+}
+
+#[derive(Args)]
+pub struct ConfigUnsetArgs {
+    /// Dotted key, like `options.nested_session`.
+    pub key: String,
+}
@@ -1,9 +1,21 @@
-use clap::{ArgAction, Args, Subcommand};
+use clap::{ArgAction, ArgGroup, Args, Subcommand};
+use clap_complete::ArgValueCandidates;
+
+use crate::completions::complete_profiles;
 
 #[derive(Subcommand)]
 pub enum ProfilesCommands {
-    /// Create new profile.
-    New,
+    /// Create new profile. Kanri asks for values that are not passed as arguments.
+    New(ProfilesNewArgs),
+
+    /// Change fields of a profile.
+    Edit(ProfilesEditArgs),
+
+    /// Rename profile.
+    Rename(ProfilesRenameArgs),
+
+    /// Copy profile under a new name.
+    Copy(ProfilesCopyArgs),
 
     /// Set profile as default.
     Set(ProfilesSetArgs),
@@ -16,23 +28,96 @@ pub enum ProfilesCommands {
 
     /// Remove profile.
     Remove(ProfilesRemoveArgs),
+
+    /// Find installed editors and shells, and create profiles for them.
+    Detect,
+}
+
+#[derive(Args)]
+pub struct ProfilesNewArgs {
+    /// Name of the new profile.
+    pub name: Option<String>,
+
+    /// Editor program. Arguments and fork mode default to the ones of its editor preset.
+    #[arg(short, long)]
+    pub editor: Option<String>,
+
+    /// Argument for the editor. Can be passed multiple times.
+    #[arg(short, long = "arg", value_name = "ARG", allow_hyphen_values = true)]
+    pub args: Vec<String>,
+
+    /// Run the editor in fork mode.
+    #[arg(short, long, action = ArgAction::SetTrue, conflicts_with = "no_fork")]
+    pub fork: bool,
+
+    /// Wait for the editor to exit.
+    #[arg(long, action = ArgAction::SetTrue)]
+    pub no_fork: bool,
+
+    /// Shell program.
+    #[arg(short, long)]
+    pub shell: Option<String>,
+}
+
+#[derive(Args)]
+#[command(group(ArgGroup::new("changes").required(true).multiple(true).args(["set", "unset"])))]
+pub struct ProfilesEditArgs {
+    /// Name of profile to edit.
+    #[arg(add = ArgValueCandidates::new(complete_profiles))]
+    pub name: String,
+
+    /// Set a field, like `editor=code`, `editor_args=[".", "-n"]` or `env.RUST_LOG=debug`.
+    /// Values are parsed as TOML, and as plain strings if that fails.
+    #[arg(long, value_name = "FIELD=VALUE")]
+    pub set: Vec<String>,
+
+    /// Remove a field, like `env.RUST_LOG`.
+    #[arg(long, value_name = "FIELD")]
+    pub unset: Vec<String>,
+}
+
+#[derive(Args)]
+pub struct ProfilesRenameArgs {
+    /// Name of profile to rename.
+    #[arg(add = ArgValueCandidates::new(complete_profiles))]
+    pub old_name: String,
+
+    /// New name of the profile.
+    pub new_name: String,
+}
+
+#[derive(Args)]
+pub struct ProfilesCopyArgs {
+    /// Name of profile to copy.
+    #[arg(add = ArgValueCandidates::new(complete_profiles))]
+    pub name: String,
+
+    /// Name of the copy.
+    pub new_name: String,
 }
 
 #[derive(Args)]
 pub struct ProfilesSetArgs {
     /// Name of profile to set as current.
+    #[arg(add = ArgValueCandidates::new(complete_profiles))]
     pub name: String,
 }
 
 #[derive(Args)]
 pub struct ProfilesGetArgs {
     /// Name of profile to get information about.
+    #[arg(add = ArgValueCandidates::new(complete_profiles))]
     pub name: String,
+
+    /// Show which profile each field comes from.
+    #[arg(long)]
+    pub origin: bool,
 }
 
 #[derive(Args)]
 pub struct ProfilesRemoveArgs {
     /// Name of profile to remove.
+    #[arg(add = ArgValueCandidates::new(complete_profiles))]
     pub name: String,
 
     /// Force removal of profile
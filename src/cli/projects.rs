@@ -1,4 +1,10 @@
 use clap::{ArgAction, Args};
+use clap_complete::ArgValueCandidates;
+
+use crate::{
+    completions::{complete_blueprints, complete_launchers, complete_profiles, complete_projects},
+    shell::Shell,
+};
 
 #[derive(Args)]
 pub struct NewArgs {
@@ -6,7 +12,7 @@ pub struct NewArgs {
     pub name: String,
 
     // Blueprint to use for a new project.
-    #[arg(short, long)]
+    #[arg(short, long, add = ArgValueCandidates::new(complete_blueprints))]
     pub blueprint: Option<String>,
 
     /// Template to use for a new project.
@@ -34,13 +40,31 @@ pub struct CloneArgs {
 
 #[derive(Args)]
 pub struct OpenArgs {
-    /// Name of the project to open.
-    pub name: String,
+    /// Name of the project to open, or `@N` for the N-th pinned project.
+    /// If omitted, Kanri asks to pick one.
+    #[arg(add = ArgValueCandidates::new(complete_projects))]
+    pub name: Option<String>,
+
+    /// File to open in the editor, optionally with a line and a column, like `src/main.rs:42:7`.
+    #[arg(conflicts_with = "shell")]
+    pub target: Option<String>,
 
     /// Open shell in this project.
     #[arg(short, long, action = ArgAction::SetTrue)]
     pub shell: bool,
 
+    /// Open the shell in a new window of the terminal from the profile.
+    #[arg(short, long, requires = "shell", action = ArgAction::SetTrue)]
+    pub new_window: bool,
+
+    /// Open the project in a tmux or zellij session, or switch to it if it already exists.
+    #[arg(short, long, conflicts_with_all = ["shell", "with", "target"], action = ArgAction::SetTrue)]
+    pub mux: bool,
+
+    /// Open the project with a launcher from the profile instead of the editor.
+    #[arg(short, long, conflicts_with = "shell", add = ArgValueCandidates::new(complete_launchers))]
+    pub with: Option<String>,
+
     /// Display the path to the project instead of opening it.
     #[arg(short, long, action = ArgAction::SetTrue)]
     pub path: bool,
@@ -48,6 +72,14 @@ pub struct OpenArgs {
     /// Disable autocomplete. Usable for integrations.
     #[arg(long, action = ArgAction::SetTrue)]
     pub skip_autocomplete: bool,
+
+    /// Do not run hooks of the project, and skip its environment variables and session layout.
+    #[arg(long, action = ArgAction::SetTrue)]
+    pub no_hooks: bool,
+
+    /// Profile to open the project with, instead of the one from the project or configuration.
+    #[arg(long, add = ArgValueCandidates::new(complete_profiles))]
+    pub profile: Option<String>,
 }
 
 #[derive(Args)]
@@ -59,23 +91,53 @@ pub struct ListArgs {
 
 #[derive(Args)]
 pub struct RenameArgs {
-    /// Old project name.
-    pub old_name: String,
+    /// Old project name or `@N` for a pinned project. If omitted, Kanri asks to pick one.
+    #[arg(add = ArgValueCandidates::new(complete_projects))]
+    pub old_name: Option<String>,
 
-    /// New project name.
-    pub new_name: String,
+    /// New project name. If omitted, Kanri asks for it.
+    pub new_name: Option<String>,
 }
 
 #[derive(Args)]
 pub struct RemoveArgs {
-    /// Name of the project to remove.
-    pub name: String,
+    /// Name of the project to remove. If omitted, Kanri asks to pick one.
+    #[arg(add = ArgValueCandidates::new(complete_projects))]
+    pub name: Option<String>,
 
     /// Confirm the removal.
     #[arg(short, long, action = ArgAction::SetTrue)]
     pub yes: bool,
 }
 
+#[derive(Args)]
+pub struct PinArgs {
+    /// Name of the project to pin.
+    #[arg(add = ArgValueCandidates::new(complete_projects))]
+    pub name: String,
+}
+
+#[derive(Args)]
+pub struct UnpinArgs {
+    /// Name or position (like `@1`) of the project to unpin.
+    #[arg(add = ArgValueCandidates::new(complete_projects))]
+    pub name: String,
+}
+
+#[derive(Args)]
+pub struct InfoArgs {
+    /// Name of the project. If omitted, Kanri asks to pick one.
+    #[arg(add = ArgValueCandidates::new(complete_projects))]
+    pub name: Option<String>,
+}
+
+#[derive(Args)]
+pub struct CurrentArgs {
+    /// Display the path to the project instead of its name.
+    #[arg(short, long, action = ArgAction::SetTrue)]
+    pub path: bool,
+}
+
 #[derive(Args)]
 pub struct BackupArgs {
     /// The path where to write backup file.
@@ -91,3 +153,17 @@ pub struct ImportArgs {
     #[arg(short, long, action = ArgAction::SetTrue)]
     pub yes: bool,
 }
+
+#[derive(Args)]
+pub struct InitArgs {
+    /// Shell to generate the integration script for.
+    #[arg(value_enum)]
+    pub shell: Shell,
+}
+
+#[derive(Args)]
+pub struct CompletionsArgs {
+    /// Shell to generate the completion script for.
+    #[arg(value_enum)]
+    pub shell: Shell,
+}
@@ -20,4 +20,8 @@ pub struct Cli {
     /// Print the version of Kanri.
     #[arg(short, long, action = ArgAction::SetTrue)]
     pub version: bool,
+
+    /// Print more details about what Kanri does.
+    #[arg(long, global = true, action = ArgAction::SetTrue)]
+    pub verbose: bool,
 }
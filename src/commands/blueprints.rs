@@ -8,10 +8,11 @@ use crate::{
         BlueprintsRemoveArgs,
     },
     config::Config,
+    editor::{ArgValues, FileTarget, expand_args},
     platform,
     program::{LaunchOptions, launch_program},
     templates::Templates,
-    terminal::{print_done, print_title},
+    terminal::{print_done, print_title, print_verbose},
 };
 
 pub fn handle(command: BlueprintsCommands) -> Result<()> {
@@ -247,12 +248,27 @@ fn open_blueprint_in_editor(blueprint_path: &Path) -> Result<()> {
     if profile.editor.is_empty() {
         bail!("Editor program name is not set in the configuration file.");
     }
+    let editor = profile.find_editor()?;
+    print_verbose(&format!("Using editor '{editor}'."));
 
-    let mut editor_args = profile.editor_args.clone();
-    editor_args.push(blueprint_path.to_string_lossy().to_string());
+    let target = FileTarget::new(blueprint_path);
+    let name = blueprint_path
+        .file_stem()
+        .map(|name| name.to_string_lossy())
+        .unwrap_or_default();
+    let values = ArgValues {
+        path: blueprint_path.parent().unwrap_or(blueprint_path),
+        name: &name,
+        target: Some(&target),
+    };
+    let editor_args = expand_args(
+        &profile.editor_args,
+        &values,
+        config.editor_presets().line_syntax(editor),
+    );
 
     let launch_options = LaunchOptions {
-        program: &profile.editor,
+        program: editor,
         args: editor_args,
         cwd: None,
         fork_mode: profile.editor_fork_mode,
@@ -1,11 +1,17 @@
 use anyhow::{Result, anyhow, bail};
+use colored::Colorize;
+use std::path::Path;
 
 use crate::{
-    cli::RecentArgs,
-    config::Config,
+    cli::{ConfigGetArgs, ConfigSetArgs, ConfigUnsetArgs, RecentArgs, ShowArgs},
+    config::{Config, parse_value_for},
+    document::{ConfigDocument, parse_key},
+    editor::{ArgValues, FileTarget, expand_args},
+    layers::{self, ConfigOrigin},
     platform,
     program::{LaunchOptions, launch_program},
-    terminal::{ask_dialog, print_done},
+    state::State,
+    terminal::{ask_dialog, print_done, print_verbose, print_warning},
 };
 
 pub fn handle_path() -> Result<()> {
@@ -17,16 +23,26 @@ pub fn handle_edit() -> Result<()> {
     let path = platform::config_file();
     let config = Config::load(&path)?;
     let profile = config.get_profile(&config.options.current_profile)?;
-    let editor = profile.editor.clone();
-    if editor.is_empty() {
+    if profile.editor.is_empty() {
         bail!("Editor program name is not set in the configuration file.");
     }
+    let editor = profile.find_editor()?;
+    print_verbose(&format!("Using editor '{editor}'."));
 
-    let mut editor_args = profile.editor_args.clone();
-    editor_args.push(path.to_string_lossy().to_string());
+    let target = FileTarget::new(&path);
+    let values = ArgValues {
+        path: path.parent().unwrap_or(&path),
+        name: "config",
+        target: Some(&target),
+    };
+    let editor_args = expand_args(
+        &profile.editor_args,
+        &values,
+        config.editor_presets().line_syntax(editor),
+    );
 
     let launch_options = LaunchOptions {
-        program: &editor,
+        program: editor,
         args: editor_args,
         fork_mode: profile.editor_fork_mode,
         quiet: false,
@@ -38,36 +54,39 @@ pub fn handle_edit() -> Result<()> {
 }
 
 pub fn handle_recent(args: RecentArgs) -> Result<()> {
-    let path = platform::config_file();
-    let mut config = Config::load(&path)?;
+    let config = Config::load(platform::config_file())?;
 
     if !config.recent.enabled {
         bail!("Recent feature is disabled in the configuration file.");
     }
 
+    let state_path = platform::state_file();
+    let _lock = State::lock(&state_path)?;
+    let mut state = State::load(&state_path)?;
     if args.clear {
-        if config.recent.recent_project.is_empty() {
+        if state.recent_project.is_empty() {
             bail!("Nothing to clear.");
         }
-        config.recent.recent_project.clear();
-        config.save(path)?;
+        state.recent_project.clear();
+        state.save(state_path)?;
         println!("Cleared recent project record.");
         return Ok(());
     }
 
-    if config.recent.recent_project.is_empty() {
+    if state.recent_project.is_empty() {
         bail!("No recent project found.");
     }
 
-    println!("{}", config.recent.recent_project);
+    println!("{}", state.recent_project);
 
     Ok(())
 }
 
 pub fn handle_reset() -> Result<()> {
     let path = platform::config_file();
-    let mut config = Config::load(&path)?;
     if ask_dialog("Reset your current configuration?", false, true)? {
+        let _lock = Config::lock(&path)?;
+        let mut config = Config::load(&path)?;
         config.reset();
         config.save(path)?;
         print_done("Configuration has been reseted.");
@@ -76,3 +95,131 @@ pub fn handle_reset() -> Result<()> {
     }
     Ok(())
 }
+
+pub fn handle_show(args: ShowArgs) -> Result<()> {
+    let path = platform::config_file();
+    let config = Config::load(&path)?;
+    if args.origin {
+        let comment = format!("# config file: {}", path.display());
+        println!("{}", comment.dimmed());
+    }
+    print_table(
+        &config,
+        &config.effective_table()?,
+        &mut Vec::new(),
+        args.origin,
+    );
+    Ok(())
+}
+
+pub fn handle_get(args: ConfigGetArgs) -> Result<()> {
+    let config = Config::load(platform::config_file())?;
+    let keys = parse_key(&args.key)?;
+    let keys: Vec<&str> = keys.iter().map(String::as_str).collect();
+    let table = config.effective_table()?;
+    let value =
+        layers::lookup(&table, &keys).ok_or_else(|| anyhow!("'{}' is not set.", args.key))?;
+
+    match value {
+        // Strings are printed without quotes, so scripts can use them as is.
+        toml::Value::String(value) => println!("{value}"),
+        toml::Value::Table(table) => {
+            let mut path = keys.iter().map(|key| key.to_string()).collect();
+            print_table(&config, table, &mut path, false);
+        }
+        value => println!("{value}"),
+    }
+    Ok(())
+}
+
+pub fn handle_set(args: ConfigSetArgs) -> Result<()> {
+    let path = platform::config_file();
+    let _lock = Config::lock(&path)?;
+    let config = Config::load(&path)?;
+    let keys = parse_key(&args.key)?;
+    let key_refs: Vec<&str> = keys.iter().map(String::as_str).collect();
+    let current = layers::lookup(&config.effective_table()?, &key_refs).cloned();
+    let value = parse_value_for(&args.value, current.as_ref());
+
+    let mut document = ConfigDocument::load(&path)?;
+    document.set(&keys, &value)?;
+    save_checked(&document, &path, &args.key)?;
+
+    print_done(&format!("Set '{}' to {value}.", args.key));
+    warn_if_overridden(&path, &key_refs)
+}
+
+pub fn handle_unset(args: ConfigUnsetArgs) -> Result<()> {
+    let path = platform::config_file();
+    let _lock = Config::lock(&path)?;
+    let keys = parse_key(&args.key)?;
+    let mut document = ConfigDocument::load(&path)?;
+    if !document.unset(&keys)? {
+        bail!("'{}' is not set in the configuration file.", args.key);
+    }
+    save_checked(&document, &path, &args.key)?;
+
+    print_done(&format!("Removed '{}'.", args.key));
+    Ok(())
+}
+
+/// Saves the document if it is a valid configuration.
+fn save_checked(document: &ConfigDocument, path: &Path, key: &str) -> Result<()> {
+    Config::from_value(document.to_value()?).map_err(|e| anyhow!("Cannot change '{key}', {e}"))?;
+    document.save(path)?;
+    Ok(())
+}
+
+/// Warns when the value in the file is hidden by a `.kanri.toml` or an environment variable.
+fn warn_if_overridden(path: &Path, keys: &[&str]) -> Result<()> {
+    let config = Config::load(path)?;
+    let origin = config.value_origin(keys);
+    if matches!(origin, ConfigOrigin::Directory(_) | ConfigOrigin::Env(_)) {
+        print_warning(&format!("The value is overridden by {origin}."));
+    }
+    Ok(())
+}
+
+/// Prints the table in the TOML format, optionally with the origin of each value.
+fn print_table(config: &Config, table: &toml::Table, path: &mut Vec<String>, origin: bool) {
+    let (tables, values): (Vec<_>, Vec<_>) = table.iter().partition(|(_, value)| value.is_table());
+    for (key, value) in values {
+        let line = format!("{} = {value}", format_key(key));
+        if !origin {
+            println!("{line}");
+            continue;
+        }
+        path.push(key.clone());
+        let keys: Vec<&str> = path.iter().map(String::as_str).collect();
+        let comment = format!("# {}", config.value_origin(&keys));
+        println!("{line}  {}", comment.dimmed());
+        path.pop();
+    }
+
+    for (key, value) in tables {
+        let Some(table) = value.as_table() else {
+            continue;
+        };
+        path.push(key.clone());
+        // Tables with only other tables inside, like `profiles`, don't need a header.
+        if table.is_empty() || table.values().any(|value| !value.is_table()) {
+            let header: Vec<String> = path.iter().map(|key| format_key(key)).collect();
+            println!("\n[{}]", header.join("."));
+        }
+        print_table(config, table, path, origin);
+        path.pop();
+    }
+}
+
+/// Quotes the key if it can't be written as a bare TOML key.
+fn format_key(key: &str) -> String {
+    let bare = !key.is_empty()
+        && key
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-');
+    if bare {
+        key.to_string()
+    } else {
+        toml::Value::String(key.to_string()).to_string()
+    }
+}
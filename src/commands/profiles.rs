@@ -1,65 +1,96 @@
-use anyhow::{Result, bail};
+use anyhow::{Result, anyhow, bail, ensure};
 use colored::Colorize;
 
 use crate::{
-    cli::{ProfilesGetArgs, ProfilesRemoveArgs, ProfilesSetArgs},
-    config::{Config, Profile},
+    cli::{
+        ProfilesCopyArgs, ProfilesEditArgs, ProfilesGetArgs, ProfilesNewArgs, ProfilesRemoveArgs,
+        ProfilesRenameArgs, ProfilesSetArgs,
+    },
+    config::{Config, ConfigError, Profile, ProgramCandidates},
+    detect::{self, detect_editors, detect_shells},
     platform,
-    terminal::{ask_dialog, ask_string_dialog, print_done, print_title},
+    terminal::{
+        ask_dialog, ask_multi_select, ask_select, ask_string_dialog, is_terminal, print_done,
+        print_title,
+    },
 };
 
-pub fn handle_new() -> Result<()> {
+pub fn handle_new(args: ProfilesNewArgs) -> Result<()> {
     let config_path = platform::config_file();
-    let mut config = Config::load(&config_path)?;
+    let config = Config::load(&config_path)?;
 
-    let profile_name = ask_string_dialog("Name for new profile?", true)?;
+    let profile_name = match args.name {
+        Some(name) => name,
+        None => ask_value("Name for new profile?")?,
+    };
+    let profile_name = profile_name.trim().to_string();
+    ensure!(!profile_name.is_empty(), "Profile name is empty.");
 
     if config.is_profile_exist(&profile_name) {
         bail!("Profile with the same name already exists.")
     }
 
-    #[allow(unused_mut)]
-    let mut editor = ask_string_dialog("Which editor you want to assign (program name)?", true)?;
+    let editor = match args.editor {
+        Some(editor) => editor,
+        None => ask_value("Which editor you want to assign (program name)?")?,
+    };
 
     if editor.is_empty() {
         bail!("Editor name is empty.")
     }
 
-    let editor_fork_mode: bool;
-    let mut editor_args: Vec<String> = Vec::with_capacity(1);
+    let presets = config.editor_presets();
+    let preset = presets.find(&editor);
+    if let Some((preset_name, _)) = preset
+        && (args.args.is_empty() || !(args.fork || args.no_fork))
+    {
+        print_done(&format!(
+            "Using settings from the '{preset_name}' editor preset."
+        ));
+    }
 
-    match editor.as_str() {
-        "code" | "code-insiders" | "codium" | "code-oss" | "cursor" | "windsurf" | "zed"
-        | "code.cmd" | "code-insiders.cmd" | "codium.cmd" | "code-oss.cmd" | "windsurf.cmd"
-        | "cursor.cmd" => {
-            #[cfg(target_os = "windows")]
-            {
-                if editor != "zed" && !editor.ends_with(".cmd") {
-                    editor.push_str(".cmd");
-                }
-            }
+    let editor_args = match preset {
+        Some((_, preset)) if args.args.is_empty() => preset.args.clone(),
+        _ => args.args,
+    };
+    let editor_fork_mode = match (args.fork, args.no_fork, preset) {
+        (true, _, _) => true,
+        (_, true, _) => false,
+        (_, _, Some((_, preset))) => preset.fork_mode,
+        _ if is_terminal() => ask_dialog("Do you want to run your editor forked?", false, true)?,
+        _ => false,
+    };
+    let editor = match preset {
+        Some((_, preset)) => preset.platform_program(&editor),
+        None => editor,
+    };
 
-            editor_fork_mode = true;
-            editor_args.push(".".to_string());
-        }
-        _ => {
-            editor_fork_mode = ask_dialog("Do you want to run your editor forked?", false, true)?;
+    let shell = match args.shell {
+        Some(shell) => shell,
+        None if is_terminal() => {
+            ask_string_dialog("Which shell you want to assign (program name)?", true)?
         }
-    }
-
-    let shell = ask_string_dialog("Which shell you want to assign (program name)?", true)?;
+        None => detect::default_shell(),
+    };
 
     if shell.is_empty() {
         bail!("Shell name is empty.")
     }
 
     let profile = Profile {
-        editor,
+        editor: editor.into(),
         editor_fork_mode,
         editor_args,
         shell,
+        ..Default::default()
     };
 
+    // The questions may take a while, so the lock is taken only to write the answers.
+    let _lock = Config::lock(&config_path)?;
+    let mut config = Config::load(&config_path)?;
+    if config.is_profile_exist(&profile_name) {
+        bail!("Profile with the same name already exists.")
+    }
     config.profiles.insert(profile_name, profile);
     config.save(config_path)?;
 
@@ -67,8 +98,84 @@ pub fn handle_new() -> Result<()> {
     Ok(())
 }
 
+fn ask_value(question: &str) -> Result<String> {
+    ensure!(
+        is_terminal(),
+        "Not enough arguments to create a profile in non-interactive mode. See `kanri profiles new --help`."
+    );
+    Ok(ask_string_dialog(question, true)?)
+}
+
+pub fn handle_edit(args: ProfilesEditArgs) -> Result<()> {
+    let config_path = platform::config_file();
+    let _lock = Config::lock(&config_path)?;
+    let mut config = Config::load(&config_path)?;
+
+    let profile = config
+        .profiles
+        .get_mut(&args.name)
+        .ok_or_else(|| ConfigError::ProfileNotFound(args.name.clone()))?;
+
+    let mut changes_parent = false;
+    for assignment in &args.set {
+        let (field, value) = assignment
+            .split_once('=')
+            .ok_or_else(|| anyhow!("Expected FIELD=VALUE, got '{assignment}'."))?;
+        let (field, value) = (field.trim(), value.trim());
+        let value = profile.parse_field_value(field, value);
+        profile.set_field(field, value)?;
+        changes_parent |= field == "extends";
+    }
+    for field in &args.unset {
+        profile.unset_field(field.trim())?;
+        changes_parent |= field.trim() == "extends";
+    }
+    if changes_parent {
+        config.rebase_profile(&args.name)?;
+    }
+
+    config.save(config_path)?;
+    print_done(&format!("Profile '{}' has been updated.", args.name));
+    Ok(())
+}
+
+pub fn handle_rename(args: ProfilesRenameArgs) -> Result<()> {
+    let config_path = platform::config_file();
+    let _lock = Config::lock(&config_path)?;
+    let mut config = Config::load(&config_path)?;
+
+    let new_name = args.new_name.trim();
+    ensure!(!new_name.is_empty(), "Profile name is empty.");
+    config.rename_profile(&args.old_name, new_name)?;
+    config.save(config_path)?;
+
+    print_done(&format!(
+        "Profile '{}' has been renamed to '{new_name}'.",
+        args.old_name
+    ));
+    Ok(())
+}
+
+pub fn handle_copy(args: ProfilesCopyArgs) -> Result<()> {
+    let config_path = platform::config_file();
+    let _lock = Config::lock(&config_path)?;
+    let mut config = Config::load(&config_path)?;
+
+    let new_name = args.new_name.trim();
+    ensure!(!new_name.is_empty(), "Profile name is empty.");
+    config.copy_profile(&args.name, new_name)?;
+    config.save(config_path)?;
+
+    print_done(&format!(
+        "Profile '{}' has been copied to '{new_name}'.",
+        args.name
+    ));
+    Ok(())
+}
+
 pub fn handle_set(args: ProfilesSetArgs) -> Result<()> {
     let config_path = platform::config_file();
+    let _lock = Config::lock(&config_path)?;
     let mut config = Config::load(&config_path)?;
 
     if !config.is_profile_exist(&args.name) {
@@ -106,15 +213,99 @@ pub fn handle_get(args: ProfilesGetArgs) -> Result<()> {
     let config = Config::load(platform::config_file())?;
     let profile = config.get_profile(&args.name)?;
 
-    print_title("Profile");
-    // There should be a better way to display it.
-    println!("  {}: {}", "Editor".bold(), profile.editor);
-    println!("  {}: {}", "Shell".bold(), profile.shell);
+    let title = if config.options.current_profile == args.name {
+        format!("Profile '{}' (current)", args.name)
+    } else {
+        format!("Profile '{}'", args.name)
+    };
+    print_title(&title);
+
+    // Shows which profile the field comes from, when asked for.
+    let origin = |field: &str| {
+        if args.origin {
+            format!(" (from {})", config.field_origin(&args.name, field))
+                .dimmed()
+                .to_string()
+        } else {
+            String::new()
+        }
+    };
+
+    if let Some(parent) = &profile.extends {
+        println!("  {}: {parent}", "Extends".bold());
+    }
+    println!(
+        "  {}: {}{}",
+        "Editor".bold(),
+        match &profile.editor {
+            ProgramCandidates::Program(editor) => editor.clone(),
+            ProgramCandidates::List(editors) => format_list(editors),
+        },
+        origin("editor")
+    );
+    println!(
+        "  {}: {}{}",
+        "Editor arguments".bold(),
+        format_list(&profile.editor_args),
+        origin("editor_args")
+    );
+    println!(
+        "  {}: {}{}",
+        "Fork mode".bold(),
+        if profile.editor_fork_mode {
+            "yes"
+        } else {
+            "no"
+        },
+        origin("editor_fork_mode")
+    );
+    println!("  {}: {}{}", "Shell".bold(), profile.shell, origin("shell"));
+    println!(
+        "  {}: {}{}",
+        "Terminal".bold(),
+        format_list(&profile.terminal),
+        origin("terminal")
+    );
+    println!(
+        "  {}: {}{}",
+        "Multiplexer".bold(),
+        profile.multiplexer.unwrap_or_default().program(),
+        origin("multiplexer")
+    );
+
+    if !profile.env.is_empty() {
+        println!("  {}:", "Environment".bold());
+        for (name, value) in &profile.env {
+            println!("    {name}={value}{}", origin(&format!("env.{name}")));
+        }
+    }
+
+    if !profile.launchers.is_empty() {
+        println!("  {}:", "Launchers".bold());
+        for (name, launcher) in &profile.launchers {
+            let mut command = vec![launcher.program().to_string()];
+            command.extend(launcher.args().iter().cloned());
+            let fork_mode = if launcher.fork_mode() { " (fork)" } else { "" };
+            println!(
+                "    {name}: {}{}{}",
+                format_list(&command),
+                fork_mode.dimmed(),
+                origin(&format!("launchers.{name}"))
+            );
+        }
+    }
     Ok(())
 }
 
+/// Formats a list of arguments the way they are written in the configuration.
+fn format_list(items: &[String]) -> String {
+    let items: Vec<String> = items.iter().map(|item| format!("{item:?}")).collect();
+    format!("[{}]", items.join(", "))
+}
+
 pub fn handle_remove(args: ProfilesRemoveArgs) -> Result<()> {
     let config_path = platform::config_file();
+    let _lock = Config::lock(&config_path)?;
     let mut config = Config::load(&config_path)?;
 
     if !config.is_profile_exist(&args.name) {
@@ -125,6 +316,19 @@ pub fn handle_remove(args: ProfilesRemoveArgs) -> Result<()> {
         bail!("Select another profile first before you can delete current one.")
     }
 
+    let children = config.profile_children(&args.name);
+    if !children.is_empty() {
+        bail!(
+            "Profile '{}' is extended by {}. Change their 'extends' first.",
+            args.name,
+            children
+                .iter()
+                .map(|child| format!("'{child}'"))
+                .collect::<Vec<_>>()
+                .join(", ")
+        )
+    }
+
     if !args.yes && !ask_dialog("Do you want to delete this profile?", false, false)? {
         print_done("Aborted");
         return Ok(());
@@ -136,3 +340,113 @@ pub fn handle_remove(args: ProfilesRemoveArgs) -> Result<()> {
 
     Ok(())
 }
+
+pub fn handle_detect() -> Result<()> {
+    let config_path = platform::config_file();
+    let config = Config::load(&config_path)?;
+    let presets = config.editor_presets();
+
+    let path_var = std::env::var_os("PATH").unwrap_or_default();
+    let editors = detect_editors(&presets, &path_var);
+    let shells = detect_shells(&path_var);
+
+    print_title("Editors");
+    if editors.is_empty() {
+        println!("  {}", "No known editors found.".dimmed());
+    }
+    for editor in &editors {
+        println!(
+            "  {} {}",
+            editor.program,
+            format!("({}, {})", editor.preset, editor.path.display()).dimmed()
+        );
+    }
+
+    print_title("Shells");
+    if shells.is_empty() {
+        println!("  {}", "No known shells found.".dimmed());
+    }
+    for shell in &shells {
+        println!(
+            "  {} {}",
+            shell.program,
+            format!("({})", shell.path.display()).dimmed()
+        );
+    }
+
+    if editors.is_empty() || !is_terminal() {
+        return Ok(());
+    }
+
+    let items: Vec<String> = editors
+        .iter()
+        .map(|editor| {
+            if config.is_profile_exist(&editor.program) {
+                format!("{} (profile exists)", editor.program)
+            } else {
+                editor.program.clone()
+            }
+        })
+        .collect();
+    let selected = ask_multi_select("Create profiles for which editors?", &items)?;
+    if selected.is_empty() {
+        print_done("No profiles have been created.");
+        return Ok(());
+    }
+
+    let shell = match shells.len() {
+        0 => detect::default_shell(),
+        1 => shells[0].program.clone(),
+        _ => {
+            // Offer the shell from the environment first.
+            let mut names: Vec<String> = shells.iter().map(|shell| shell.program.clone()).collect();
+            if let Some(current) = platform::env_shell().map(|shell| detect::program_stem(&shell))
+                && let Some(position) = names.iter().position(|name| *name == current)
+            {
+                let current = names.remove(position);
+                names.insert(0, current);
+            }
+            let index = ask_select("Which shell to use in these profiles?", &names, true)?;
+            names.swap_remove(index)
+        }
+    };
+
+    // The questions may take a while, so the lock is taken only to write the answers.
+    let _lock = Config::lock(&config_path)?;
+    let mut config = Config::load(&config_path)?;
+    let mut created: Vec<&str> = Vec::new();
+    for editor in selected.iter().map(|&index| &editors[index]) {
+        if config.is_profile_exist(&editor.program) {
+            println!(
+                "  {}",
+                format!("Profile '{}' already exists, skipped.", editor.program).dimmed()
+            );
+            continue;
+        }
+
+        let Some(preset) = presets.get(&editor.preset) else {
+            continue;
+        };
+        let profile = Profile {
+            editor: preset.platform_program(&editor.program).into(),
+            editor_args: preset.args.clone(),
+            editor_fork_mode: preset.fork_mode,
+            shell: shell.clone(),
+            ..Default::default()
+        };
+        config.profiles.insert(editor.program.clone(), profile);
+        created.push(&editor.program);
+    }
+
+    if created.is_empty() {
+        print_done("No profiles have been created.");
+        return Ok(());
+    }
+
+    config.save(config_path)?;
+    print_done(&format!(
+        "Created profiles: {}. Switch to one with `kanri profiles set <name>`.",
+        created.join(", ")
+    ));
+    Ok(())
+}
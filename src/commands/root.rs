@@ -1,18 +1,36 @@
 use anyhow::{Result, anyhow, bail, ensure};
 use colored::Colorize;
 use indexmap::IndexMap;
-use std::{fs, time::Duration};
+use std::{
+    fs,
+    path::Path,
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
 
 use crate::{
     autocomplete,
     backup::{Backup, load_backup, save_backup},
     blueprints::{engine::BlueprintEngine, storage::Blueprints},
-    cli::{BackupArgs, CloneArgs, ImportArgs, ListArgs, NewArgs, OpenArgs, RemoveArgs, RenameArgs},
+    cli::{
+        BackupArgs, CloneArgs, CompletionsArgs, CurrentArgs, ImportArgs, InfoArgs, InitArgs,
+        ListArgs, NewArgs, OpenArgs, PinArgs, RemoveArgs, RenameArgs, UnpinArgs,
+    },
+    completions,
     config::Config,
+    editor::{ArgValues, FileTarget, expand_args},
+    hooks::{HookContext, HookEvent, LifecycleEvent, ProjectTrust, run_lifecycle_hooks},
     library::{CloneOptions, Library, validate_project_name},
+    mux::{self, Multiplexer, MuxError, MuxLayout, MuxSession, Tmux},
     platform::{self},
-    program::{LaunchOptions, launch_program},
-    terminal::{ask_dialog, generate_progress, is_terminal, print_done, print_error, print_title},
+    program::{LaunchOptions, ProgramError, check_program, launch_program},
+    project::{self, ProjectMeta},
+    session::{self, Handoff, NestedSessionMode, Session},
+    shell,
+    state::State,
+    terminal::{
+        ask_dialog, ask_fuzzy_select, ask_string_dialog, generate_progress, is_terminal,
+        print_done, print_error, print_title, print_verbose, print_warning,
+    },
 };
 
 fn resolve_project_name(
@@ -20,14 +38,84 @@ fn resolve_project_name(
     config: &Config,
     projects: &Library,
     skip: bool,
-) -> Option<String> {
+    destructive: bool,
+) -> Result<Option<String>> {
     if project_name == "-" && config.recent.enabled {
-        Some(config.recent.recent_project.clone())
+        let state = State::load(platform::state_file())?;
+        Ok(Some(state.recent_project))
     } else if config.autocomplete.enabled && !skip {
         let projects_list: Vec<&str> = projects.get_names().iter().map(|i| i.as_str()).collect();
-        autocomplete::autocomplete(project_name, &projects_list, config)
+        Ok(autocomplete::autocomplete(
+            project_name,
+            &projects_list,
+            config,
+            destructive,
+        ))
     } else {
-        Some(project_name.to_string())
+        Ok(Some(project_name.to_string()))
+    }
+}
+
+/// Resolves the project name from arguments or asks the user to pick one.
+fn get_project_name(
+    name: Option<&str>,
+    config: &Config,
+    projects: &Library,
+    skip: bool,
+    destructive: bool,
+    question: &str,
+) -> Result<String> {
+    let Some(name) = name else {
+        return pick_project(projects, question);
+    };
+
+    if let Some(position) = name.strip_prefix('@').and_then(|v| v.parse::<usize>().ok()) {
+        let state = State::load(platform::state_file())?;
+        return state
+            .get_pin(position)
+            .map(|pin| pin.to_string())
+            .ok_or_else(|| anyhow!("There is no pinned project at position {position}."));
+    }
+
+    resolve_project_name(name, config, projects, skip, destructive)?
+        .ok_or_else(|| anyhow!("Project not found."))
+}
+
+fn pick_project(projects: &Library, question: &str) -> Result<String> {
+    ensure!(
+        is_terminal(),
+        "Project name is required in non-interactive mode."
+    );
+    ensure!(!projects.is_empty(), "No projects found.");
+
+    let state = State::load(platform::state_file())?;
+    let names: Vec<&str> = projects.get_names().iter().map(|i| i.as_str()).collect();
+    let ranked = state.rank_projects(&names);
+    let items: Vec<String> = ranked
+        .iter()
+        .map(|name| picker_item(name, projects.get(name).unwrap(), &state))
+        .collect();
+
+    let index = ask_fuzzy_select(question, &items, true)?;
+    Ok(ranked[index].to_string())
+}
+
+fn picker_item(name: &str, path: &Path, state: &State) -> String {
+    let mut hints: Vec<String> = Vec::new();
+    if let Some(position) = state.pin_position(name) {
+        hints.push(format!("@{position}"));
+    }
+    if let Some(kind) = project::detect_kind(path) {
+        hints.push(kind.to_string());
+    }
+    if let Ok(meta) = ProjectMeta::load(path) {
+        hints.extend(meta.tags.iter().map(|tag| format!("#{tag}")));
+    }
+
+    if hints.is_empty() {
+        name.to_string()
+    } else {
+        format!("{name}  [{}]", hints.join(", "))
     }
 }
 
@@ -77,7 +165,13 @@ pub fn handle_new(args: NewArgs) -> Result<()> {
                 format!("Generated '{}' from blueprint '{}'.", args.name, blueprint,).as_str(),
             );
         }
-        return Ok(());
+        return run_config_hooks(
+            &config,
+            LifecycleEvent::PostCreate,
+            &args.name,
+            &projects_dir.join(&args.name),
+            None,
+        );
     }
 
     print_done(&format!(
@@ -85,7 +179,13 @@ pub fn handle_new(args: NewArgs) -> Result<()> {
         args.name
     ));
 
-    Ok(())
+    run_config_hooks(
+        &config,
+        LifecycleEvent::PostCreate,
+        &args.name,
+        &projects_dir.join(&args.name),
+        None,
+    )
 }
 
 pub fn handle_clone(args: CloneArgs) -> Result<()> {
@@ -105,19 +205,32 @@ pub fn handle_clone(args: CloneArgs) -> Result<()> {
     projects.clone(&clone_options)?;
 
     print_done("Repository has been cloned.");
-    Ok(())
+
+    let name = clone_options.directory_name();
+    run_config_hooks(
+        &config,
+        LifecycleEvent::PostClone,
+        &name,
+        &projects.base_path().join(&name),
+        None,
+    )
 }
 
 pub fn handle_open(args: OpenArgs) -> Result<()> {
-    let config_path = platform::config_file();
-    let mut config = Config::load(&config_path)?;
+    let config = Config::load(platform::config_file())?;
     let projects = Library::new(
         &config.options.projects_directory,
         config.options.display_hidden,
     )?;
 
-    let name = resolve_project_name(&args.name, &config, &projects, args.skip_autocomplete)
-        .ok_or_else(|| anyhow!("Project not found."))?;
+    let name = get_project_name(
+        args.name.as_deref(),
+        &config,
+        &projects,
+        args.skip_autocomplete,
+        false,
+        "Which project to open?",
+    )?;
 
     let project = projects.get(&name);
     if project.is_none() {
@@ -130,19 +243,75 @@ pub fn handle_open(args: OpenArgs) -> Result<()> {
         return Ok(());
     }
 
-    if !is_terminal() {
+    // A new terminal window doesn't need this terminal, so it can be opened from app launchers.
+    if !args.new_window && !is_terminal() {
         return Err(anyhow!(
             "Opening projects in non-interactive mode is not supported"
         ));
     }
 
-    let profile = config.get_profile(&config.options.current_profile)?;
+    let session = Session::current();
+    if args.shell
+        && !args.new_window
+        && let Some(session) = &session
+        && !start_nested_session(&config, session, &name, &args)?
+    {
+        return Ok(());
+    }
+
+    // Profile from arguments goes first, then the one from project metadata.
+    let meta = ProjectMeta::load(path)?;
+    let profile_name = args
+        .profile
+        .as_deref()
+        .or(meta.profile.as_deref())
+        .unwrap_or(&config.options.current_profile);
+    let profile = config.get_profile(profile_name)?;
 
-    let (program, launch_args, fork_mode) = if args.shell {
-        (&profile.shell, Vec::<String>::new(), false)
+    let launcher = match &args.with {
+        Some(launcher_name) => Some(profile.launchers.get(launcher_name).ok_or_else(|| {
+            anyhow!("Launcher '{launcher_name}' is not defined in profile '{profile_name}'.")
+        })?),
+        None => None,
+    };
+    if let (Some(launcher), Some(launcher_name)) = (launcher, &args.with) {
+        ensure!(
+            args.target.is_none() || launcher.takes_file(),
+            "Launcher '{launcher_name}' doesn't take a file. Add `{{file}}` to its arguments to open files with it."
+        );
+    }
+
+    let (program, launch_args, fork_mode) = if let Some(launcher) = launcher {
+        (
+            launcher.program(),
+            launcher.args().to_vec(),
+            launcher.fork_mode(),
+        )
+    } else if args.new_window {
+        let (terminal, terminal_args) = profile.terminal.split_first().ok_or_else(|| {
+            anyhow!("Profile '{profile_name}' has no `terminal` to open a new window with.")
+        })?;
+        let uses_shell = terminal_args.iter().any(|arg| arg.contains("{shell}"));
+        ensure!(
+            !uses_shell || !profile.shell.is_empty(),
+            "Required program is not specified in configuration file."
+        );
+        let terminal_args = terminal_args
+            .iter()
+            .map(|arg| arg.replace("{shell}", &profile.shell))
+            .collect();
+        (terminal.as_str(), terminal_args, true)
+    } else if args.mux {
+        // Arguments depend on whether the session exists, so they are added later.
+        let multiplexer = profile.multiplexer.unwrap_or_default();
+        (multiplexer.program(), Vec::new(), false)
+    } else if args.shell {
+        (profile.shell.as_str(), Vec::<String>::new(), false)
     } else {
+        let editor = profile.find_editor()?;
+        print_verbose(&format!("Using editor '{editor}'."));
         (
-            &profile.editor,
+            editor,
             profile.editor_args.clone(),
             profile.editor_fork_mode,
         )
@@ -153,47 +322,325 @@ pub fn handle_open(args: OpenArgs) -> Result<()> {
         "Required program is not specified in configuration file."
     );
 
-    let mut launch_options = LaunchOptions {
+    let target = args
+        .target
+        .as_deref()
+        .map(|target| FileTarget::parse(target).resolve(path));
+    let values = ArgValues {
+        path,
+        name: &name,
+        target: target.as_ref(),
+    };
+    let launch_args = expand_args(
+        &launch_args,
+        &values,
+        config.editor_presets().line_syntax(program),
+    );
+
+    // Hooks, environment variables and the session layout of the project can run any program,
+    // so they are used only if the user trusts them.
+    let trust = ProjectTrust::load(path, &meta)?;
+    let trusted = !args.no_hooks && is_project_trusted(&name, path, &trust)?;
+    if trusted && let Some(hooks) = trust.hooks() {
+        let events: &[HookEvent] = if args.shell || args.mux {
+            &[HookEvent::Open, HookEvent::Shell]
+        } else {
+            &[HookEvent::Open]
+        };
+        hooks.run(events, path, &name)?;
+    }
+
+    // Later sources override earlier ones, and variables set by Kanri always win.
+    let mut env = profile.env.clone();
+    if trusted {
+        env.extend(meta.load_env(path)?);
+    }
+    if let Some(launcher_env) = launcher.and_then(|launcher| launcher.env()) {
+        env.extend(launcher_env.clone());
+    }
+    env.insert("KANRI_PROJECT_NAME".to_string(), name.clone());
+    env.insert(
+        "KANRI_PROJECT_PATH".to_string(),
+        path.to_string_lossy().to_string(),
+    );
+    env.insert("KANRI_PROFILE".to_string(), profile_name.to_string());
+    if args.new_window || args.mux {
+        // The new window or multiplexer session is not nested in this session, and this
+        // process doesn't wait for it.
+        env.extend(session::session_env(None, None));
+    } else if args.shell {
+        env.extend(session::session_env(
+            session.as_ref(),
+            Some(std::process::id()),
+        ));
+    }
+
+    let launch_args = if args.mux {
+        let multiplexer = profile.multiplexer.unwrap_or_default();
+        let env: Vec<_> = env.clone().into_iter().collect();
+        // Without trust, the session gets a single window with the shell.
+        let default_layout = MuxLayout::default();
+        let session = MuxSession {
+            name: &mux::session_name(&name),
+            path,
+            shell: &profile.shell,
+            env: &env,
+            layout: if trusted { &meta.mux } else { &default_layout },
+        };
+        prepare_mux_session(multiplexer, &session)?
+    } else {
+        launch_args
+    };
+
+    let launch_options = LaunchOptions {
         program,
         args: launch_args,
         cwd: Some(path),
         fork_mode,
-        quiet: false,
-        env: None,
+        quiet: args.new_window,
+        env: Some(env.into_iter().collect()),
     };
 
-    if args.shell {
-        let env_map = Vec::from([(String::from("KANRI_SESSION"), "1".to_string())]);
-        launch_options.env = Some(env_map);
+    // A blocking session returns only when it ends, which is too late for `post_open`. The hook
+    // runs before it, but only if the program can be started.
+    if !fork_mode {
+        check_program(&launch_options)?;
+        run_config_hooks(&config, LifecycleEvent::PostOpen, &name, path, None)?;
+    }
+
+    // Nested sessions leave a handoff here when they replace this session.
+    let handoff_path = Handoff::path(&platform::sessions_dir(), std::process::id());
+    let in_this_terminal = args.shell && !args.new_window;
+    if in_this_terminal {
+        Handoff::take(&handoff_path)?;
         println!(
             "{}",
             "======== STARTING SHELL SESSION ========".bold().white()
         );
     }
 
-    launch_program(launch_options)?;
-
-    if args.shell {
+    let launched = launch_program(launch_options);
+    // The project has been opened even if the session ended with an error.
+    let started = matches!(
+        launched,
+        Ok(()) | Err(ProgramError::NonZeroExitCode(_) | ProgramError::ProcessInterrupted)
+    );
+    if started {
+        record_open(&name, &config)?;
+    }
+    if in_this_terminal && started {
         println!(
             "{}",
             "========  SHELL SESSION ENDED  ========".bold().white()
         );
     }
-
-    if config.recent.enabled && name != config.recent.recent_project {
-        config.recent.recent_project = name;
-        config.save(config_path)?;
-    }
+    launched?;
 
     if fork_mode {
-        // Because only editor could be launched in fork mode.
-        print_done("Editor launched.");
+        run_config_hooks(&config, LifecycleEvent::PostOpen, &name, path, None)?;
+
+        // Shells are launched in fork mode only in a new window.
+        match &args.with {
+            Some(launcher_name) => print_done(&format!("Launched '{launcher_name}'.")),
+            None if args.new_window => print_done("Opened a new terminal window."),
+            None => print_done("Editor launched."),
+        }
         return Ok(());
     }
 
+    if in_this_terminal && let Some(handoff) = Handoff::take(&handoff_path)? {
+        return handle_open(OpenArgs {
+            name: Some(handoff.project_name),
+            target: None,
+            shell: true,
+            new_window: false,
+            mux: false,
+            with: None,
+            path: false,
+            skip_autocomplete: true,
+            no_hooks: handoff.no_hooks,
+            profile: handoff.profile,
+        });
+    }
+
     Ok(())
 }
 
+/// Records that the project has been opened. The state is locked only for the update, since
+/// another project may be opened from this session later.
+fn record_open(name: &str, config: &Config) -> Result<()> {
+    let state_path = platform::state_file();
+    let _lock = State::lock(&state_path)?;
+    let mut state = State::load(&state_path)?;
+    state.record_open(name);
+    if config.recent.enabled {
+        state.recent_project = name.to_string();
+    }
+    state.save(state_path)?;
+    Ok(())
+}
+
+/// Creates the multiplexer session of the project unless it exists, and returns arguments
+/// of the multiplexer that attach to it.
+fn prepare_mux_session(multiplexer: Multiplexer, session: &MuxSession) -> Result<Vec<String>> {
+    match multiplexer {
+        Multiplexer::Tmux => {
+            let tmux = Tmux::new();
+            if tmux.has_session(session.name)? {
+                print_verbose(&format!("Attaching to tmux session '{}'.", session.name));
+            } else {
+                print_verbose(&format!("Creating tmux session '{}'.", session.name));
+                tmux.create_session(session)?;
+            }
+            // Attaching from inside tmux would nest it.
+            let inside_tmux = std::env::var_os(mux::TMUX_VAR).is_some();
+            Ok(tmux.attach_args(session.name, inside_tmux))
+        }
+        Multiplexer::Zellij => {
+            if std::env::var_os(mux::ZELLIJ_VAR).is_some() {
+                return Err(MuxError::NestedZellij.into());
+            }
+            let exists = mux::zellij_has_session(session.name)?;
+            let layout = session
+                .layout
+                .zellij_layout
+                .as_ref()
+                .map(|layout| session.path.join(layout));
+            Ok(mux::zellij_args(session.name, layout.as_deref(), exists))
+        }
+    }
+}
+
+/// Decides what to do when a shell session is opened inside another Kanri session.
+/// Returns `true` if a nested session should be started.
+fn start_nested_session(
+    config: &Config,
+    session: &Session,
+    name: &str,
+    args: &OpenArgs,
+) -> Result<bool> {
+    let current = session.project_name.as_deref().unwrap_or("another project");
+
+    match (config.options.nested_session, session.id) {
+        (NestedSessionMode::Refuse, _) => bail!(
+            "You are already in a shell session of '{current}'. Exit it first, or change `options.nested_session` in the configuration."
+        ),
+        (NestedSessionMode::Warn, _) | (NestedSessionMode::Replace, None) => {
+            print_warning(&format!(
+                "Starting a nested shell session inside the session of '{current}' (depth {}).",
+                session.depth + 1
+            ));
+            Ok(true)
+        }
+        (NestedSessionMode::Replace, Some(id)) => {
+            let question = format!(
+                "You are already in a shell session of '{current}'. Replace it with '{name}'?"
+            );
+            if !ask_dialog(&question, true, true)? {
+                print_done("Aborted.");
+                return Ok(false);
+            }
+
+            let handoff = Handoff {
+                project_name: name.to_string(),
+                profile: args.profile.clone(),
+                no_hooks: args.no_hooks,
+            };
+            handoff.save(&Handoff::path(&platform::sessions_dir(), id))?;
+
+            // The shell integration exits the current shell when it sees this code.
+            if std::env::var_os(session::INTEGRATION_VAR).is_some() {
+                std::process::exit(session::REPLACE_EXIT_CODE);
+            }
+            print_done(&format!(
+                "Exit the current shell session to switch to '{name}'."
+            ));
+            Ok(false)
+        }
+    }
+}
+
+/// Whether the user trusts the hooks, environment variables and session layout of the project.
+/// The user is asked the first time they are seen and again every time they change.
+fn is_project_trusted(name: &str, path: &Path, trust: &ProjectTrust) -> Result<bool> {
+    if trust.is_empty() {
+        return Ok(true);
+    }
+
+    let state_path = platform::state_file();
+    let fingerprint = trust.fingerprint();
+    if State::load(&state_path)?.is_hook_trusted(path, &fingerprint) {
+        return Ok(true);
+    }
+
+    if !is_terminal() {
+        print_warning(&format!(
+            "Hooks, environment variables and session layout of project '{name}' have not been trusted yet, so they were skipped. Open it in a terminal to review them."
+        ));
+        return Ok(false);
+    }
+
+    for (title, text) in trust.parts() {
+        print_title(title);
+        for line in text.lines() {
+            println!("  {}", line.dimmed());
+        }
+    }
+
+    let question = format!(
+        "Project '{name}' has new or changed hooks, environment variables or session layout. Do you trust them?"
+    );
+    if !ask_dialog(&question, false, true)? {
+        print_done("Hooks, environment variables and session layout have been skipped.");
+        return Ok(false);
+    }
+
+    // Other projects may be opened while the user reads the hooks.
+    let _lock = State::lock(&state_path)?;
+    let mut state = State::load(&state_path)?;
+    state.trust_hook(path, &fingerprint);
+    state.save(&state_path)?;
+    Ok(true)
+}
+
+/// Runs global hooks for the event from the configuration.
+///
+/// A failed `pre_remove` hook cancels the removal. Failures of other hooks are only reported,
+/// because the action has already happened by the time they run.
+fn run_config_hooks(
+    config: &Config,
+    event: LifecycleEvent,
+    project_name: &str,
+    project_path: &Path,
+    old_name: Option<&str>,
+) -> Result<()> {
+    let hooks = match event {
+        LifecycleEvent::PostCreate => &config.hooks.post_create,
+        LifecycleEvent::PostClone => &config.hooks.post_clone,
+        LifecycleEvent::PreRemove => &config.hooks.pre_remove,
+        LifecycleEvent::PostRename => &config.hooks.post_rename,
+        LifecycleEvent::PostOpen => &config.hooks.post_open,
+    };
+
+    let context = HookContext {
+        event,
+        project_name,
+        project_path,
+        old_name,
+    };
+
+    match run_lifecycle_hooks(hooks, &context) {
+        Ok(()) => Ok(()),
+        Err(e) if event == LifecycleEvent::PreRemove => {
+            bail!("Removal has been cancelled: {e}")
+        }
+        Err(e) => {
+            print_error(&e.to_string());
+            Ok(())
+        }
+    }
+}
+
 pub fn handle_list(args: ListArgs) -> Result<()> {
     let config = Config::load(platform::config_file())?;
     let projects = Library::new(
@@ -206,27 +653,192 @@ pub fn handle_list(args: ListArgs) -> Result<()> {
         return Ok(());
     }
 
-    let recent = &config.recent.recent_project;
+    let state = State::load(platform::state_file())?;
+    let recent = &state.recent_project;
+
+    // Pinned projects always go first.
+    let mut names: Vec<&String> = projects.get_names();
+    names.sort_by_key(|name| state.pin_position(name).unwrap_or(usize::MAX));
 
     if !args.pure {
         print_title("Your projects");
     }
-    for name in projects.get_names() {
+    for name in names {
         if args.pure {
             println!("{}", name);
         } else {
-            let is_recent = if name == recent.as_str() {
-                "(recent)".dimmed()
-            } else {
+            let mut marks: Vec<String> = Vec::new();
+            if let Some(position) = state.pin_position(name) {
+                marks.push(format!("@{position}"));
+            }
+            if name == recent.as_str() {
+                marks.push("recent".to_string());
+            }
+            let marks = if marks.is_empty() {
                 "".dimmed()
+            } else {
+                format!("({})", marks.join(", ")).dimmed()
             };
-            println!("  {} {is_recent}", name);
+            println!("  {} {marks}", name);
         }
     }
 
     Ok(())
 }
 
+pub fn handle_pin(args: PinArgs) -> Result<()> {
+    let config = Config::load(platform::config_file())?;
+    let projects = Library::new(
+        &config.options.projects_directory,
+        config.options.display_hidden,
+    )?;
+
+    let name = get_project_name(
+        Some(&args.name),
+        &config,
+        &projects,
+        false,
+        false,
+        "Which project to pin?",
+    )?;
+    ensure!(projects.contains(&name), "Project not found.");
+
+    let state_path = platform::state_file();
+    let _lock = State::lock(&state_path)?;
+    let mut state = State::load(&state_path)?;
+    ensure!(state.pin(&name), "Project '{name}' is already pinned.");
+    state.save(state_path)?;
+
+    let position = state.pin_position(&name).unwrap_or_default();
+    print_done(&format!(
+        "Pinned '{name}'. Open it with `kanri open @{position}`."
+    ));
+    Ok(())
+}
+
+pub fn handle_unpin(args: UnpinArgs) -> Result<()> {
+    let state_path = platform::state_file();
+    let _lock = State::lock(&state_path)?;
+    let mut state = State::load(&state_path)?;
+
+    let name = match args
+        .name
+        .strip_prefix('@')
+        .and_then(|v| v.parse::<usize>().ok())
+    {
+        Some(position) => state
+            .get_pin(position)
+            .map(|pin| pin.to_string())
+            .ok_or_else(|| anyhow!("There is no pinned project at position {position}."))?,
+        None => args.name,
+    };
+
+    ensure!(state.unpin(&name), "Project '{name}' is not pinned.");
+    state.save(state_path)?;
+
+    print_done(&format!("Unpinned '{name}'."));
+    Ok(())
+}
+
+pub fn handle_info(args: InfoArgs) -> Result<()> {
+    let config = Config::load(platform::config_file())?;
+    let projects = Library::new(
+        &config.options.projects_directory,
+        config.options.display_hidden,
+    )?;
+
+    let name = get_project_name(
+        args.name.as_deref(),
+        &config,
+        &projects,
+        false,
+        false,
+        "Which project to show?",
+    )?;
+    let path = projects
+        .get(&name)
+        .ok_or_else(|| anyhow!("Project not found."))?;
+    let meta = ProjectMeta::load(path)?;
+    let state = State::load(platform::state_file())?;
+
+    print_title("Project");
+    println!("  {}: {}", "Name".bold(), name);
+    println!("  {}: {}", "Path".bold(), path.display());
+    println!(
+        "  {}: {}",
+        "Type".bold(),
+        project::detect_kind(path).unwrap_or("unknown")
+    );
+    if !meta.tags.is_empty() {
+        println!("  {}: {}", "Tags".bold(), meta.tags.join(", "));
+    }
+    if let Some(profile) = &meta.profile {
+        println!("  {}: {}", "Profile".bold(), profile);
+    }
+    match state.history.get(&name) {
+        Some(entry) => println!(
+            "  {}: {} ({} times)",
+            "Last opened".bold(),
+            format_elapsed(entry.last_opened),
+            entry.open_count
+        ),
+        None => println!("  {}: never", "Last opened".bold()),
+    }
+
+    Ok(())
+}
+
+fn format_elapsed(timestamp: u64) -> String {
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or_default();
+    let elapsed = now.saturating_sub(timestamp);
+
+    match elapsed {
+        0..60 => "just now".to_string(),
+        60..3600 => format!("{} minutes ago", elapsed / 60),
+        3600..86400 => format!("{} hours ago", elapsed / 3600),
+        _ => format!("{} days ago", elapsed / 86400),
+    }
+}
+
+/// Prints the project that the shell session was opened for. Outside of sessions,
+/// the project is found by the current directory.
+pub fn handle_current(args: CurrentArgs) -> Result<()> {
+    let config = Config::load(platform::config_file())?;
+    let projects = Library::new(
+        &config.options.projects_directory,
+        config.options.display_hidden,
+    )?;
+
+    let (name, path) = match Session::current() {
+        Some(Session {
+            project_name: Some(name),
+            project_path,
+            ..
+        }) => {
+            let path = project_path.or_else(|| projects.get(&name).cloned());
+            (name, path)
+        }
+        _ => {
+            let current_dir = std::env::current_dir()?;
+            let name = projects
+                .find_by_path(&current_dir)
+                .ok_or_else(|| anyhow!("Not in a project."))?;
+            (name.to_string(), projects.get(name).cloned())
+        }
+    };
+
+    if args.path {
+        let path = path.ok_or_else(|| anyhow!("Project '{name}' not found."))?;
+        println!("{}", path.to_string_lossy());
+    } else {
+        println!("{name}");
+    }
+    Ok(())
+}
+
 pub fn handle_rename(args: RenameArgs) -> Result<()> {
     let config = Config::load(platform::config_file())?;
     let mut projects = Library::new(
@@ -234,14 +846,52 @@ pub fn handle_rename(args: RenameArgs) -> Result<()> {
         config.options.display_hidden,
     )?;
 
-    validate_project_name(&args.new_name)?;
+    let old_name = get_project_name(
+        args.old_name.as_deref(),
+        &config,
+        &projects,
+        false,
+        true,
+        "Which project to rename?",
+    )?;
+    ensure!(projects.contains(&old_name), "Project not found.");
+    let new_name = match args.new_name {
+        Some(name) => name,
+        None => {
+            ensure!(
+                is_terminal(),
+                "New project name is required in non-interactive mode."
+            );
+            ask_string_dialog(&format!("New name for '{old_name}'?"), true)?
+        }
+    };
+
+    validate_project_name(&new_name)?;
+
+    projects.rename(&old_name, &new_name)?;
+
+    let old_path = projects.base_path().join(&old_name);
+    let new_path = projects.base_path().join(&new_name);
+    let state_path = platform::state_file();
+    let lock = State::lock(&state_path)?;
+    let mut state = State::load(&state_path)?;
+    state.rename_project(&old_name, &new_name, &old_path, &new_path);
+    state.save(state_path)?;
+    // Hooks may run Kanri, which would wait for the lock.
+    drop(lock);
 
-    projects.rename(&args.old_name, &args.new_name)?;
     print_done(&format!(
         "Project '{}' has been renamed to '{}'.",
-        args.old_name, args.new_name
+        old_name, new_name
     ));
-    Ok(())
+
+    run_config_hooks(
+        &config,
+        LifecycleEvent::PostRename,
+        &new_name,
+        &new_path,
+        Some(&old_name),
+    )
 }
 
 pub fn handle_remove(args: RemoveArgs) -> Result<()> {
@@ -251,8 +901,14 @@ pub fn handle_remove(args: RemoveArgs) -> Result<()> {
         config.options.display_hidden,
     )?;
 
-    let project_name = resolve_project_name(&args.name, &config, &projects, false)
-        .ok_or_else(|| anyhow!("Project not found."))?;
+    let project_name = get_project_name(
+        args.name.as_deref(),
+        &config,
+        &projects,
+        false,
+        true,
+        "Which project to remove?",
+    )?;
 
     if is_terminal() {
         if !args.yes
@@ -273,6 +929,15 @@ pub fn handle_remove(args: RemoveArgs) -> Result<()> {
         }
     }
 
+    let project_path = projects.base_path().join(&project_name);
+    run_config_hooks(
+        &config,
+        LifecycleEvent::PreRemove,
+        &project_name,
+        &project_path,
+        None,
+    )?;
+
     let spinner = generate_progress().with_message("Removing project...");
 
     spinner.enable_steady_tick(Duration::from_millis(100));
@@ -283,6 +948,12 @@ pub fn handle_remove(args: RemoveArgs) -> Result<()> {
     }
     spinner.finish_and_clear();
 
+    let state_path = platform::state_file();
+    let _lock = State::lock(&state_path)?;
+    let mut state = State::load(&state_path)?;
+    state.remove_project(&project_name);
+    state.save(state_path)?;
+
     print_done(&format!("Project '{project_name}' has been removed."));
     Ok(())
 }
@@ -337,7 +1008,9 @@ pub fn handle_import(args: ImportArgs) -> Result<()> {
     let backup = load_backup(args.file)?;
 
     // Write config
-    backup.config.save(platform::config_file())?;
+    let config_path = platform::config_file();
+    let _lock = Config::lock(&config_path)?;
+    backup.config.save(&config_path)?;
 
     // Write blueprints
     let mut failed_blueprints: Vec<&str> = Vec::new();
@@ -370,6 +1043,20 @@ pub fn handle_import(args: ImportArgs) -> Result<()> {
     Ok(())
 }
 
+pub fn handle_init(args: InitArgs) -> Result<()> {
+    let config = Config::load(platform::config_file())?;
+    print!(
+        "{}",
+        shell::init_script(args.shell, &config.options.projects_directory)
+    );
+    Ok(())
+}
+
+pub fn handle_completions(args: CompletionsArgs) -> Result<()> {
+    completions::write_registration(args.shell, &mut std::io::stdout())?;
+    Ok(())
+}
+
 const KANRI_ZEN: [&str; 10] = [
     "Projects should be simple.",
     "Each command does one thing well.",
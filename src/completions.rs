@@ -0,0 +1,166 @@
+use std::{ffi::OsString, io::Write, path::Path};
+
+use clap_complete::{
+    CompletionCandidate,
+    env::{Bash, Elvish, EnvCompleter, Fish, Powershell, Shells, Zsh},
+};
+
+use crate::{blueprints::storage::Blueprints, config::Config, library::Library, platform, shell};
+
+/// Environment variable that switches Kanri into completion mode.
+pub const COMPLETE_VAR: &str = "COMPLETE";
+
+/// Shells that can request dynamic completions from Kanri.
+pub const SHELLS: Shells<'static> = Shells(&[&Bash, &Elvish, &Fish, &Powershell, &Zsh, &Nushell]);
+
+/// Writes the script that registers Kanri completions in the shell.
+pub fn write_registration(shell: shell::Shell, buf: &mut dyn Write) -> std::io::Result<()> {
+    let completer: &dyn EnvCompleter = match shell {
+        shell::Shell::Bash => &Bash,
+        shell::Shell::Zsh => &Zsh,
+        shell::Shell::Fish => &Fish,
+        shell::Shell::Powershell => &Powershell,
+        shell::Shell::Nushell => &Nushell,
+    };
+    completer.write_registration(COMPLETE_VAR, "kanri", "kanri", "kanri", buf)
+}
+
+/// Completes names of projects in the library.
+pub fn complete_projects() -> Vec<CompletionCandidate> {
+    let Ok(config) = Config::load(platform::config_file()) else {
+        return Vec::new();
+    };
+    let Ok(projects) = Library::new(
+        &config.options.projects_directory,
+        config.options.display_hidden,
+    ) else {
+        return Vec::new();
+    };
+
+    projects
+        .get_names()
+        .into_iter()
+        .map(CompletionCandidate::new)
+        .collect()
+}
+
+/// Completes names of blueprints.
+pub fn complete_blueprints() -> Vec<CompletionCandidate> {
+    let Ok(blueprints) = Blueprints::load_from_path(&platform::blueprints_dir()) else {
+        return Vec::new();
+    };
+
+    blueprints
+        .get_blueprints()
+        .iter()
+        .map(CompletionCandidate::new)
+        .collect()
+}
+
+/// Completes names of profiles.
+pub fn complete_profiles() -> Vec<CompletionCandidate> {
+    let Ok(config) = Config::load(platform::config_file()) else {
+        return Vec::new();
+    };
+
+    config
+        .profiles
+        .keys()
+        .map(|name| {
+            let candidate = CompletionCandidate::new(name);
+            if *name == config.options.current_profile {
+                candidate.help(Some("current".into()))
+            } else {
+                candidate
+            }
+        })
+        .collect()
+}
+
+/// Completes names of launchers from the current profile.
+pub fn complete_launchers() -> Vec<CompletionCandidate> {
+    let Ok(config) = Config::load(platform::config_file()) else {
+        return Vec::new();
+    };
+    let Ok(profile) = config.get_profile(&config.options.current_profile) else {
+        return Vec::new();
+    };
+
+    profile
+        .launchers
+        .keys()
+        .map(CompletionCandidate::new)
+        .collect()
+}
+
+/// Nushell completion adapter. Nushell uses a single external completer for all commands,
+/// so the registration chains to the previously configured one.
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub struct Nushell;
+
+impl EnvCompleter for Nushell {
+    fn name(&self) -> &'static str {
+        "nushell"
+    }
+
+    fn is(&self, name: &str) -> bool {
+        name == "nushell" || name == "nu"
+    }
+
+    fn write_registration(
+        &self,
+        var: &str,
+        _name: &str,
+        bin: &str,
+        completer: &str,
+        buf: &mut dyn Write,
+    ) -> Result<(), std::io::Error> {
+        write!(
+            buf,
+            r#"# Kanri completions for Nushell.
+# Save the output to a file and source it in your config.nu:
+#   kanri completions nushell | save -f ~/.config/nushell/kanri-completions.nu
+#   source ~/.config/nushell/kanri-completions.nu
+
+let kanri_previous_completer = $env.config.completions.external.completer?
+$env.config.completions.external.enable = true
+$env.config.completions.external.completer = {{|spans|
+    if ($spans.0 == "{bin}") {{
+        with-env {{ {var}: "nushell" }} {{ ^"{completer}" -- ...$spans }}
+        | lines
+        | each {{|line|
+            let parts = ($line | split row "\t")
+            {{ value: $parts.0, description: ($parts.1? | default "") }}
+        }}
+    }} else if ($kanri_previous_completer != null) {{
+        do $kanri_previous_completer $spans
+    }}
+}}
+"#
+        )
+    }
+
+    fn write_complete(
+        &self,
+        cmd: &mut clap::Command,
+        args: Vec<OsString>,
+        current_dir: Option<&Path>,
+        buf: &mut dyn Write,
+    ) -> Result<(), std::io::Error> {
+        let index = args.len() - 1;
+        let completions = clap_complete::engine::complete(cmd, args, index, current_dir)?;
+
+        for candidate in completions {
+            write!(buf, "{}", candidate.get_value().to_string_lossy())?;
+            if let Some(help) = candidate.get_help() {
+                write!(
+                    buf,
+                    "\t{}",
+                    help.to_string().lines().next().unwrap_or_default()
+                )?;
+            }
+            writeln!(buf)?;
+        }
+        Ok(())
+    }
+}
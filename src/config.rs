@@ -1,14 +1,28 @@
-use crate::{migrations, platform};
+use crate::{
+    detect,
+    document::ConfigDocument,
+    editor::{EditorPreset, EditorPresets},
+    files::FileLock,
+    layers::{self, ConfigLayer, ConfigOrigin},
+    migrations,
+    mux::Multiplexer,
+    platform,
+    resolve::{self, OS_SECTIONS},
+    session::NestedSessionMode,
+    state::{State, StateError},
+    terminal,
+};
 use indexmap::{IndexMap, indexmap};
 use serde::{Deserialize, Serialize};
 use std::{
+    ffi::OsStr,
     fs,
     io::ErrorKind,
     path::{Path, PathBuf},
 };
 use thiserror::Error;
 
-const CONFIG_VERSION: &str = "2";
+pub(crate) const CONFIG_VERSION: &str = "3";
 const PROJECTS_DIRECTORY_NAMES: [&str; 13] = [
     "Projects",
     "Code",
@@ -68,8 +82,23 @@ pub enum ConfigError {
     #[error("profile '{0}' was not found.")]
     ProfileNotFound(String),
 
+    #[error("profile '{0}' already exists.")]
+    ProfileAlreadyExists(String),
+
+    #[error("invalid field '{0}': {1}.")]
+    InvalidField(String, String),
+
+    #[error("none of these programs were found in PATH: {0}.")]
+    ProgramsNotFound(String),
+
     #[error("file system error occurred: {0}.")]
     FileSystemError(#[from] std::io::Error),
+
+    #[error("cannot lock the configuration file: {0}.")]
+    LockFailed(std::io::Error),
+
+    #[error(transparent)]
+    State(#[from] StateError),
 }
 
 #[derive(Deserialize, Serialize, Clone)]
@@ -80,41 +109,64 @@ pub struct Config {
     pub profiles: IndexMap<String, Profile>,
     pub recent: RecentOptions,
     pub autocomplete: AutocompleteOptions,
+    pub hooks: HooksOptions,
+    /// Editor presets added by the user, by name.
+    #[serde(skip_serializing_if = "IndexMap::is_empty")]
+    pub editors: IndexMap<String, EditorPreset>,
+    /// The configuration file as it was loaded, used to write back only changed values.
+    #[serde(skip)]
+    source: Option<Box<ConfigSource>>,
+}
+
+#[derive(Clone)]
+struct ConfigSource {
+    /// The operating system the configuration was resolved for.
+    os: String,
+    /// The file contents, before OS sections and `extends` were applied.
+    raw: toml::Table,
+    /// The resolved configuration right after loading.
+    resolved: toml::Table,
+    /// Layers applied on top of the file, in order.
+    layers: Vec<ConfigLayer>,
+    /// The file with its comments and formatting, which is changed on save.
+    document: Option<ConfigDocument>,
 }
 
 impl Default for Config {
+    /// Configuration with the platform's default programs. Serde uses it on every load, so it
+    /// doesn't search `PATH`. New configuration files are created with [`Config::detected`].
     fn default() -> Self {
-        // Getting default editor
-        #[allow(unused_mut)]
-        let mut editor = platform::default_editor().to_string();
-        let mut editor_args: Vec<String> = Vec::new();
-        let mut editor_fork_mode = false;
-
-        match editor.as_str() {
-            "code" | "code-insiders" | "codium" | "code-oss" | "cursor" | "windsurf" | "zed"
-            | "code.cmd" | "code-insiders.cmd" | "codium.cmd" | "code-oss.cmd" | "windsurf.cmd"
-            | "cursor.cmd" => {
-                #[cfg(target_os = "windows")]
-                {
-                    if editor != "zed" && !editor.ends_with(".cmd") {
-                        editor.push_str(".cmd");
-                    }
-                }
-                editor_args.push(".".to_string());
-                editor_fork_mode = true;
-            }
-            _ => {}
-        }
+        Self::with_programs(
+            platform::fallback_editor().to_string(),
+            platform::fallback_shell().to_string(),
+        )
+    }
+}
 
-        // Getting default shell
-        let shell = platform::default_shell().to_string();
+impl Config {
+    /// Default configuration with the editor and the shell detected from the environment and
+    /// `PATH`.
+    pub fn detected() -> Self {
+        Self::with_programs(detect::default_editor(), detect::default_shell())
+    }
+
+    fn with_programs(editor: String, shell: String) -> Self {
+        let (editor, editor_args, editor_fork_mode) = match EditorPresets::builtin().find(&editor) {
+            Some((_, preset)) => (
+                preset.platform_program(&editor),
+                preset.args.clone(),
+                preset.fork_mode,
+            ),
+            None => (editor.to_string(), Vec::new(), false),
+        };
 
         let profiles = indexmap! {
             String::from("default") => Profile {
-                editor,
+                editor: editor.into(),
                 editor_args,
                 editor_fork_mode,
                 shell,
+                ..Default::default()
             }
         };
 
@@ -124,6 +176,9 @@ impl Default for Config {
             profiles,
             recent: RecentOptions::default(),
             autocomplete: AutocompleteOptions::default(),
+            hooks: HooksOptions::default(),
+            editors: IndexMap::new(),
+            source: None,
         }
     }
 }
@@ -131,10 +186,353 @@ impl Default for Config {
 #[derive(Deserialize, Serialize, Default, Clone)]
 #[serde(default, deny_unknown_fields)]
 pub struct Profile {
-    pub editor: String,
+    /// Name of the profile this one inherits fields from.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub extends: Option<String>,
+    pub editor: ProgramCandidates,
     pub editor_args: Vec<String>,
     pub editor_fork_mode: bool,
     pub shell: String,
+    /// Terminal emulator with arguments, used by `kanri open --shell --new-window`.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub terminal: Vec<String>,
+    /// Terminal multiplexer used by `kanri open --mux`. Defaults to tmux.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub multiplexer: Option<Multiplexer>,
+    /// Environment variables for the editor and shell sessions.
+    #[serde(skip_serializing_if = "IndexMap::is_empty")]
+    pub env: IndexMap<String, String>,
+    /// Additional programs that can open projects, by name.
+    #[serde(skip_serializing_if = "IndexMap::is_empty")]
+    pub launchers: IndexMap<String, Launcher>,
+    /// Fields for Linux only.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub linux: Option<ProfileOverrides>,
+    /// Fields for macOS only.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub macos: Option<ProfileOverrides>,
+    /// Fields for Windows only.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub windows: Option<ProfileOverrides>,
+    /// The resolved parent profile. Fields with the same values are not written on save.
+    #[serde(skip)]
+    pub(crate) base: Option<Box<Profile>>,
+}
+
+/// Fields of a profile that override the common ones on one operating system.
+#[derive(Deserialize, Serialize, Default, Clone, Debug, PartialEq, Eq)]
+#[serde(default, deny_unknown_fields)]
+pub struct ProfileOverrides {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub editor: Option<ProgramCandidates>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub editor_args: Option<Vec<String>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub editor_fork_mode: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub shell: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub terminal: Option<Vec<String>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub multiplexer: Option<Multiplexer>,
+    #[serde(skip_serializing_if = "IndexMap::is_empty")]
+    pub env: IndexMap<String, String>,
+    #[serde(skip_serializing_if = "IndexMap::is_empty")]
+    pub launchers: IndexMap<String, Launcher>,
+}
+
+/// A program, or a list of programs that are tried in order.
+#[derive(Deserialize, Serialize, Clone, Debug, PartialEq, Eq)]
+#[serde(untagged)]
+pub enum ProgramCandidates {
+    Program(String),
+    List(Vec<String>),
+}
+
+impl Default for ProgramCandidates {
+    fn default() -> Self {
+        ProgramCandidates::Program(String::new())
+    }
+}
+
+impl From<String> for ProgramCandidates {
+    fn from(program: String) -> Self {
+        ProgramCandidates::Program(program)
+    }
+}
+
+impl From<&str> for ProgramCandidates {
+    fn from(program: &str) -> Self {
+        ProgramCandidates::Program(program.to_string())
+    }
+}
+
+impl ProgramCandidates {
+    pub fn as_slice(&self) -> &[String] {
+        match self {
+            ProgramCandidates::Program(program) => std::slice::from_ref(program),
+            ProgramCandidates::List(programs) => programs,
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.as_slice().iter().all(|program| program.is_empty())
+    }
+
+    /// Returns the first program found in `path_var`, which has the format of the `PATH`
+    /// variable. A single program is returned as it is, even if it is not in `PATH`.
+    pub fn find(&self, path_var: &OsStr) -> Result<&str, ConfigError> {
+        let programs = match self {
+            ProgramCandidates::Program(program) => return Ok(program),
+            ProgramCandidates::List(programs) => programs,
+        };
+        programs
+            .iter()
+            .filter(|program| !program.is_empty())
+            .find(|program| platform::find_program(program, path_var).is_some())
+            .map(String::as_str)
+            .ok_or_else(|| ConfigError::ProgramsNotFound(programs.join(", ")))
+    }
+}
+
+impl Profile {
+    /// Returns the editor to launch: the first one from the list that is installed.
+    pub fn find_editor(&self) -> Result<&str, ConfigError> {
+        let path_var = std::env::var_os("PATH").unwrap_or_default();
+        self.editor.find(&path_var)
+    }
+
+    /// Sets a field by its dotted path, like `editor` or `env.RUST_LOG`.
+    pub fn set_field(&mut self, field: &str, value: toml::Value) -> Result<(), ConfigError> {
+        self.update_field(field, |table, key| {
+            table.insert(key.to_string(), value);
+            true
+        })
+    }
+
+    /// Parses a value from the command line for a field, by the type of the field. Values for
+    /// text fields, like `shell` or variables in `env`, stay strings.
+    pub fn parse_field_value(&self, field: &str, value: &str) -> toml::Value {
+        // Optional fields are left out of the profile, so their types come from a sample.
+        // Fields in OS sections have the same types as the common ones.
+        let key = OS_SECTIONS
+            .iter()
+            .find_map(|os| field.strip_prefix(os)?.strip_prefix('.'))
+            .unwrap_or(field);
+        let schema = Profile {
+            extends: Some(String::new()),
+            multiplexer: Some(Multiplexer::default()),
+            ..Profile::default()
+        };
+        let current = toml::Table::try_from(self)
+            .ok()
+            .and_then(|table| field_value(&table, field).cloned())
+            .or_else(|| {
+                let schema = toml::Table::try_from(&schema).ok()?;
+                field_value(&schema, key).cloned()
+            })
+            .or_else(|| {
+                key.starts_with("env.")
+                    .then(|| toml::Value::String(String::new()))
+            });
+        parse_value_for(value, current.as_ref())
+    }
+
+    /// Removes a field by its dotted path. Removed top-level fields get their default values,
+    /// or the values of the parent profile if the profile extends another one.
+    pub fn unset_field(&mut self, field: &str) -> Result<(), ConfigError> {
+        self.update_field(field, |table, key| table.remove(key).is_some())?;
+        if let Some(value) = self.inherited_value(field) {
+            self.set_field(field, value)?;
+        }
+        Ok(())
+    }
+
+    /// Whether the field has the same value as in the parent profile.
+    pub fn is_inherited(&self, field: &str) -> bool {
+        let Ok(table) = toml::Table::try_from(self) else {
+            return false;
+        };
+        self.inherited_value(field)
+            .is_some_and(|value| field_value(&table, field) == Some(&value))
+    }
+
+    fn inherited_value(&self, field: &str) -> Option<toml::Value> {
+        field_value(&self.inherited_fields()?, field).cloned()
+    }
+
+    /// Fields of the parent profile that this profile inherits.
+    fn inherited_fields(&self) -> Option<toml::Table> {
+        let mut base = toml::Table::try_from(self.base.as_deref()?).ok()?;
+        base.retain(|key, _| key != "extends" && !OS_SECTIONS.contains(&key));
+        Some(base)
+    }
+
+    /// Fields of the profile as they are written to the configuration file.
+    /// Fields inherited from the parent profile are left out.
+    fn own_fields(&self) -> Result<toml::Table, toml::ser::Error> {
+        let mut table = toml::Table::try_from(self)?;
+        let Some(base) = self.inherited_fields() else {
+            return Ok(table);
+        };
+
+        table.retain(|key, value| {
+            let Some(base_value) = base.get(key) else {
+                return true;
+            };
+            if let (toml::Value::Table(table), toml::Value::Table(base_table)) =
+                (&mut *value, base_value)
+            {
+                table.retain(|key, value| base_table.get(key) != Some(value));
+                return !table.is_empty();
+            }
+            value != base_value
+        });
+        Ok(table)
+    }
+
+    fn update_field(
+        &mut self,
+        field: &str,
+        update: impl FnOnce(&mut toml::Table, &str) -> bool,
+    ) -> Result<(), ConfigError> {
+        let invalid = |message: &str| ConfigError::InvalidField(field.to_string(), message.into());
+
+        let base = self.base.take();
+        let root = toml::Table::try_from(&*self).map_err(|_| ConfigError::FormatFailed);
+        self.base = base;
+        let mut root = root?;
+        let mut keys: Vec<&str> = field.split('.').collect();
+        let last = keys.pop().filter(|key| !key.is_empty());
+        let last = last.ok_or_else(|| invalid("field name is empty"))?;
+
+        let mut table = &mut root;
+        for key in keys {
+            table = table
+                .entry(key)
+                .or_insert_with(|| toml::Value::Table(toml::Table::new()))
+                .as_table_mut()
+                .ok_or_else(|| invalid(&format!("'{key}' is not a table")))?;
+        }
+        if !update(table, last) {
+            return Err(invalid("field is not set"));
+        }
+
+        let profile: Profile = root
+            .try_into()
+            .map_err(|e: toml::de::Error| invalid(e.message()))?;
+        *self = Profile {
+            base: self.base.take(),
+            ..profile
+        };
+        Ok(())
+    }
+}
+
+/// Returns the table under `key`, creating it if it is missing.
+fn table_entry<'a>(table: &'a mut toml::Table, key: &str) -> &'a mut toml::Table {
+    let entry = table
+        .entry(key)
+        .or_insert_with(|| toml::Value::Table(toml::Table::new()));
+    if !entry.is_table() {
+        *entry = toml::Value::Table(toml::Table::new());
+    }
+    entry.as_table_mut().unwrap()
+}
+
+/// Returns the value of a field by its dotted path.
+fn field_value<'a>(table: &'a toml::Table, field: &str) -> Option<&'a toml::Value> {
+    let (tables, key) = match field.rsplit_once('.') {
+        Some((tables, key)) => (Some(tables), key),
+        None => (None, field),
+    };
+    let mut table = table;
+    for name in tables.into_iter().flat_map(|tables| tables.split('.')) {
+        table = table.get(name)?.as_table()?;
+    }
+    table.get(key)
+}
+
+/// Parses a value from the command line as TOML. Values that are not valid TOML,
+/// like `code`, are treated as strings.
+pub fn parse_value(value: &str) -> toml::Value {
+    format!("value = {value}")
+        .parse::<toml::Table>()
+        .ok()
+        .and_then(|mut table| table.remove("value"))
+        .unwrap_or_else(|| toml::Value::String(value.to_string()))
+}
+
+/// Parses a value from the command line for a field that has the value `current`. Values
+/// for string fields stay strings, so `123` is not turned into a number, unless they are
+/// lists or tables.
+pub fn parse_value_for(value: &str, current: Option<&toml::Value>) -> toml::Value {
+    let parsed = parse_value(value);
+    match (current, &parsed) {
+        (Some(toml::Value::String(_)), toml::Value::Array(_) | toml::Value::Table(_)) => parsed,
+        (Some(toml::Value::String(_)), _) => toml::Value::String(value.to_string()),
+        _ => parsed,
+    }
+}
+
+/// A program started in the project with `kanri open --with <name>`.
+#[derive(Deserialize, Serialize, Clone, Debug, PartialEq, Eq)]
+#[serde(untagged)]
+pub enum Launcher {
+    /// Just a program, e.g. `git = "lazygit"`.
+    Program(String),
+    /// A program with arguments, e.g. `test = ["cargo", "watch", "-x", "test"]`.
+    Command(Vec<String>),
+    /// A program with all options.
+    Detailed(DetailedLauncher),
+}
+
+#[derive(Deserialize, Serialize, Default, Clone, Debug, PartialEq, Eq)]
+#[serde(default, deny_unknown_fields)]
+pub struct DetailedLauncher {
+    pub program: String,
+    pub args: Vec<String>,
+    pub fork_mode: bool,
+    #[serde(skip_serializing_if = "IndexMap::is_empty")]
+    pub env: IndexMap<String, String>,
+}
+
+impl Launcher {
+    pub fn program(&self) -> &str {
+        match self {
+            Launcher::Program(program) => program,
+            Launcher::Command(command) => command.first().map_or("", |program| program),
+            Launcher::Detailed(launcher) => &launcher.program,
+        }
+    }
+
+    pub fn args(&self) -> &[String] {
+        match self {
+            Launcher::Program(_) => &[],
+            Launcher::Command(command) => command.get(1..).unwrap_or_default(),
+            Launcher::Detailed(launcher) => &launcher.args,
+        }
+    }
+
+    /// Whether the arguments say where to put a file to open, with `{file}`. Kanri doesn't
+    /// know how other programs open files, so only these launchers can open them.
+    pub fn takes_file(&self) -> bool {
+        self.args().iter().any(|arg| arg.contains("{file}"))
+    }
+
+    pub fn fork_mode(&self) -> bool {
+        match self {
+            Launcher::Detailed(launcher) => launcher.fork_mode,
+            _ => false,
+        }
+    }
+
+    pub fn env(&self) -> Option<&IndexMap<String, String>> {
+        match self {
+            Launcher::Detailed(launcher) => Some(&launcher.env),
+            _ => None,
+        }
+    }
 }
 
 #[derive(Deserialize, Serialize, Clone)]
@@ -143,6 +541,24 @@ pub struct GeneralOptions {
     pub projects_directory: PathBuf,
     pub current_profile: String,
     pub display_hidden: bool,
+    pub nested_session: NestedSessionMode,
+    /// Options for Linux only.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub linux: Option<OsOptions>,
+    /// Options for macOS only.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub macos: Option<OsOptions>,
+    /// Options for Windows only.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub windows: Option<OsOptions>,
+}
+
+/// Options that override the common ones on one operating system.
+#[derive(Deserialize, Serialize, Default, Clone, Debug, PartialEq, Eq)]
+#[serde(default, deny_unknown_fields)]
+pub struct OsOptions {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub projects_directory: Option<PathBuf>,
 }
 
 #[derive(Deserialize, Serialize, Clone)]
@@ -165,52 +581,293 @@ impl Default for AutocompleteOptions {
 #[serde(default, deny_unknown_fields)]
 pub struct RecentOptions {
     pub enabled: bool,
-    pub recent_project: String,
 }
 
 impl Default for RecentOptions {
     fn default() -> Self {
-        Self {
-            enabled: true,
-            recent_project: String::new(),
-        }
+        Self { enabled: true }
     }
 }
 
+/// Hooks that run on project lifecycle events.
+#[derive(Deserialize, Serialize, Default, Clone)]
+#[serde(default, deny_unknown_fields)]
+pub struct HooksOptions {
+    pub post_create: Vec<LifecycleHook>,
+    pub post_clone: Vec<LifecycleHook>,
+    pub pre_remove: Vec<LifecycleHook>,
+    pub post_rename: Vec<LifecycleHook>,
+    pub post_open: Vec<LifecycleHook>,
+}
+
+/// A single hook: either a program with arguments or a Lua script.
+#[derive(Deserialize, Serialize, Clone, Debug, PartialEq, Eq)]
+#[serde(untagged, deny_unknown_fields)]
+pub enum LifecycleHook {
+    Command { command: Vec<String> },
+    Script { script: PathBuf },
+}
+
 impl Default for GeneralOptions {
     fn default() -> Self {
         Self {
             projects_directory: find_projects_directory(),
             current_profile: "default".to_string(),
             display_hidden: false,
+            nested_session: NestedSessionMode::default(),
+            linux: None,
+            macos: None,
+            windows: None,
         }
     }
 }
 
 impl Config {
+    /// Loads the configuration file with the layers on top of it: the nearest `.kanri.toml`
+    /// of the current directory, then `KANRI_<SECTION>_<KEY>` environment variables.
     pub fn load(path: impl AsRef<Path>) -> Result<Self, ConfigError> {
-        let content = fs::read_to_string(&path).map_err(|e| match e.kind() {
-            ErrorKind::PermissionDenied => ConfigError::ReadPermissionDenied,
-            ErrorKind::NotFound => ConfigError::FileNotFound,
-            _ => ConfigError::FileSystemError(e),
-        })?;
+        let path = path.as_ref();
+        let mut layers = Vec::new();
+        let directory_config = std::env::current_dir()
+            .ok()
+            .and_then(|dir| layers::find_directory_config(&dir))
+            // The configuration file itself may be named `.kanri.toml`.
+            .filter(|directory_config| directory_config != path);
+        if let Some(directory_config) = directory_config {
+            let (layer, ignored) = ConfigLayer::load_directory(&directory_config)?;
+            if !ignored.is_empty() {
+                let ignored: Vec<_> = ignored.iter().map(|s| format!("[{s}]")).collect();
+                terminal::print_warning(&format!(
+                    "Ignoring {} in {}. A {} can only set [options] and [autocomplete].",
+                    ignored.join(", "),
+                    directory_config.display(),
+                    layers::DIRECTORY_CONFIG_NAME
+                ));
+            }
+            layers.push(layer);
+        }
+        layers.extend(ConfigLayer::from_env(
+            std::env::vars(),
+            &Self::env_schema(),
+        )?);
 
-        let mut value: toml::Value = toml::from_str(&content)
-            .map_err(|e: toml::de::Error| ConfigError::BadConfiguration(e.to_string()))?;
+        Self::load_with_layers(path, platform::state_file(), layers)
+    }
+
+    /// Loads the configuration file with the layers on top of it. Runtime data found in an
+    /// old configuration file is moved to the state file at `state_path`.
+    pub fn load_with_layers(
+        path: impl AsRef<Path>,
+        state_path: impl AsRef<Path>,
+        layers: Vec<ConfigLayer>,
+    ) -> Result<Self, ConfigError> {
+        let mut document = ConfigDocument::load(&path)?;
+        let mut lock = None;
+        if migrations::needs_migration(document.as_document_mut()) {
+            // Commands that change the configuration hold the lock while they load it, and save
+            // the migrated file themselves. Waiting for the lock would never end for them, so
+            // the file is saved here only if no one holds the lock.
+            lock = Self::try_lock(&path)?;
+            if lock.is_some() {
+                // Another process may have migrated the file before the lock was taken.
+                document = ConfigDocument::load(&path)?;
+            }
+        }
+        let was_migrated = migrations::needs_migration(document.as_document_mut());
+        if was_migrated {
+            let _lock = State::lock(&state_path)?;
+            let mut state = State::load(&state_path)?;
+            migrations::migrate_config(document.as_document_mut(), &mut state)?;
+            // Save the state first, so the data is not lost if the configuration can't be saved.
+            state.save(&state_path)?;
+        }
+        let value = document.to_value()?;
 
-        let was_migrated = migrations::migrate_config(&mut value)?;
+        let mut config = Self::from_layers(value, layers, std::env::consts::OS)?;
+        if let Some(source) = &mut config.source {
+            source.document = Some(document);
+        }
+
+        if was_migrated && lock.is_some() {
+            // A read-only configuration file is migrated again every time it is loaded.
+            match config.save(&path) {
+                Err(ConfigError::WritePermissionDenied) => terminal::print_warning(&format!(
+                    "{} is read-only, so it can't be migrated to version {CONFIG_VERSION}. Kanri migrates it every time it runs until the file is updated.",
+                    path.as_ref().display()
+                )),
+                result => result?,
+            }
+        }
+
+        Ok(config)
+    }
+
+    /// Creates the configuration from a TOML value for the current operating system.
+    pub fn from_value(value: toml::Value) -> Result<Self, ConfigError> {
+        Self::from_value_for_os(value, std::env::consts::OS)
+    }
+
+    /// Creates the configuration from a TOML value. Sections for the operating system `os`
+    /// are applied, and profiles are merged into the ones they extend.
+    pub fn from_value_for_os(value: toml::Value, os: &str) -> Result<Self, ConfigError> {
+        Self::from_layers(value, Vec::new(), os)
+    }
+
+    /// Creates the configuration from a TOML value with the layers on top of it.
+    ///
+    /// Directory layers are merged before OS sections are applied, so they override the values
+    /// from OS sections of the file. Environment layers override the resolved values.
+    pub fn from_layers(
+        mut value: toml::Value,
+        layers: Vec<ConfigLayer>,
+        os: &str,
+    ) -> Result<Self, ConfigError> {
+        let raw = match &value {
+            toml::Value::Table(table) => table.clone(),
+            _ => {
+                return Err(ConfigError::BadConfiguration(
+                    "configuration root must be a table".to_string(),
+                ));
+            }
+        };
 
-        let config: Config = value
+        let (env_layers, directory_layers): (Vec<_>, Vec<_>) = layers
+            .iter()
+            .partition(|layer| matches!(layer.origin, ConfigOrigin::Env(_)));
+        if let toml::Value::Table(table) = &mut value {
+            for layer in directory_layers {
+                resolve::merge_layer(table, &layer.table, os);
+            }
+        }
+
+        if let Some(options) = value.get_mut("options").and_then(toml::Value::as_table_mut) {
+            resolve::apply_os_section(options, os);
+        }
+        let order = resolve::resolve_profiles(&mut value, os)?;
+
+        if let toml::Value::Table(table) = &mut value {
+            for layer in env_layers {
+                for (section, fields) in &layer.table {
+                    if let toml::Value::Table(fields) = fields {
+                        table_entry(table, section).extend(fields.clone());
+                    }
+                }
+            }
+        }
+
+        let mut config: Config = value
             .try_into()
             .map_err(|e: toml::de::Error| ConfigError::BadConfiguration(e.to_string()))?;
 
-        if was_migrated {
-            config.save(path)?;
+        for name in order {
+            config.rebase_profile(&name)?;
         }
-
+        let resolved = toml::Table::try_from(&config).map_err(|_| ConfigError::FormatFailed)?;
+        config.source = Some(Box::new(ConfigSource {
+            os: os.to_string(),
+            raw,
+            resolved,
+            layers,
+            document: None,
+        }));
         Ok(config)
     }
 
+    /// Sections and keys that `KANRI_<SECTION>_<KEY>` variables can override.
+    pub fn env_schema() -> toml::Table {
+        let mut schema = toml::Table::new();
+        let sections = [
+            ("options", toml::Table::try_from(GeneralOptions::default())),
+            ("recent", toml::Table::try_from(RecentOptions::default())),
+            (
+                "autocomplete",
+                toml::Table::try_from(AutocompleteOptions::default()),
+            ),
+        ];
+        for (name, section) in sections {
+            if let Ok(section) = section {
+                schema.insert(name.to_string(), toml::Value::Table(section));
+            }
+        }
+        schema
+    }
+
+    /// Returns the configuration as it is written to the file.
+    ///
+    /// Values from OS sections and parent profiles are not written to the fields they were
+    /// applied to. Changed values are written where they came from.
+    pub fn to_table(&self) -> Result<toml::Table, ConfigError> {
+        let mut table = toml::Table::try_from(self).map_err(|_| ConfigError::FormatFailed)?;
+        let mut profiles = toml::Table::new();
+        for (name, profile) in &self.profiles {
+            let fields = profile
+                .own_fields()
+                .map_err(|_| ConfigError::FormatFailed)?;
+            profiles.insert(name.clone(), toml::Value::Table(fields));
+        }
+
+        let Some(source) = &self.source else {
+            table.insert("profiles".to_string(), toml::Value::Table(profiles));
+            return Ok(table);
+        };
+
+        let mut raw = source.raw.clone();
+        let mut old = source.resolved.clone();
+        let old_options = old.remove("options");
+        let old_profiles = old.remove("profiles");
+        let new_options = table.remove("options");
+        let new_profiles = table.remove("profiles");
+        resolve::apply_changes(&mut raw, &old, &table, None, None);
+
+        let as_table = |value: Option<toml::Value>| match value {
+            Some(toml::Value::Table(table)) => table,
+            _ => toml::Table::new(),
+        };
+        let os = Some(source.os.as_str());
+
+        let old_options = as_table(old_options);
+        let new_options = as_table(new_options);
+        let raw_options = table_entry(&mut raw, "options");
+        resolve::apply_changes(raw_options, &old_options, &new_options, os, None);
+
+        let old_profiles = as_table(old_profiles);
+        let new_profiles = as_table(new_profiles);
+        let raw_profiles = table_entry(&mut raw, "profiles");
+        let mut written = toml::Table::new();
+        for (name, profile) in &self.profiles {
+            let (Some(toml::Value::Table(old)), Some(toml::Value::Table(new))) =
+                (old_profiles.get(name), new_profiles.get(name))
+            else {
+                written.insert(name.clone(), profiles[name].clone());
+                continue;
+            };
+            let (mut raw_profile, in_file) = match raw_profiles.remove(name) {
+                Some(toml::Value::Table(raw_profile)) => (raw_profile, true),
+                _ => (toml::Table::new(), false),
+            };
+            let inherited = profile.inherited_fields();
+            resolve::apply_changes(&mut raw_profile, old, new, os, inherited.as_ref());
+            // Profiles that are not in the file are written only when they are changed.
+            if in_file || !raw_profile.is_empty() {
+                written.insert(name.clone(), toml::Value::Table(raw_profile));
+            }
+        }
+        *raw_profiles = written;
+        Ok(raw)
+    }
+
+    /// Locks the configuration file at `path` until the lock is dropped. Commands take the
+    /// lock before they load the configuration to change it, so they don't overwrite changes
+    /// made by other processes at the same time.
+    pub fn lock(path: impl AsRef<Path>) -> Result<FileLock, ConfigError> {
+        FileLock::acquire(path.as_ref()).map_err(ConfigError::LockFailed)
+    }
+
+    /// Locks the configuration file at `path` if no one holds the lock, without waiting.
+    pub fn try_lock(path: impl AsRef<Path>) -> Result<Option<FileLock>, ConfigError> {
+        FileLock::try_acquire(path.as_ref()).map_err(ConfigError::LockFailed)
+    }
+
     pub fn save(&self, path: impl AsRef<Path>) -> Result<(), ConfigError> {
         let path = path.as_ref();
         if let Some(parent) = path.parent()
@@ -221,13 +878,15 @@ impl Config {
                 _ => Err(ConfigError::FileSystemError(e)),
             };
         }
-        let content = toml::to_string(self).map_err(|_| ConfigError::FormatFailed)?;
-        fs::write(path, content).map_err(|e| match e.kind() {
-            ErrorKind::PermissionDenied => ConfigError::WritePermissionDenied,
-            ErrorKind::NotFound => ConfigError::FileNotFound,
-            ErrorKind::StorageFull => ConfigError::StorageFull,
-            _ => ConfigError::FileSystemError(e),
-        })
+
+        // Only changed values are written, so the rest of the file keeps its comments.
+        let mut document = self
+            .source
+            .as_ref()
+            .and_then(|source| source.document.clone())
+            .unwrap_or_default();
+        document.sync(&self.to_table()?);
+        document.save(path)
     }
 
     pub fn get_profile(&self, name: &str) -> Result<&Profile, ConfigError> {
@@ -238,11 +897,192 @@ impl Config {
         }
     }
 
+    /// Links the profile to the profile it extends, so inherited fields are not written on save.
+    pub fn rebase_profile(&mut self, name: &str) -> Result<(), ConfigError> {
+        let profile = self.get_profile(name)?;
+        let base = match &profile.extends {
+            Some(parent) => {
+                let mut chain = vec![name.to_string()];
+                let mut current = parent;
+                loop {
+                    if chain.contains(current) {
+                        chain.push(current.clone());
+                        return Err(ConfigError::BadConfiguration(format!(
+                            "profiles extend each other in a cycle: {}",
+                            chain.join(" -> ")
+                        )));
+                    }
+                    let ancestor = self.profiles.get(current).ok_or_else(|| {
+                        ConfigError::BadConfiguration(format!(
+                            "profile '{}' extends '{current}', which does not exist",
+                            chain.last().unwrap()
+                        ))
+                    })?;
+                    chain.push(current.clone());
+                    match &ancestor.extends {
+                        Some(next) => current = next,
+                        None => break,
+                    }
+                }
+                Some(Box::new(self.profiles[parent].clone()))
+            }
+            None => None,
+        };
+
+        if let Some(profile) = self.profiles.get_mut(name) {
+            profile.base = base;
+        }
+        Ok(())
+    }
+
+    /// Returns where the field of the profile `name` is defined: the name of the profile,
+    /// or the profile and the section of the operating system, like `work.linux`.
+    pub fn field_origin(&self, name: &str, field: &str) -> String {
+        let os = self.os();
+        let mut current = name;
+        while let Some(profile) = self.profiles.get(current) {
+            let in_section = toml::Table::try_from(profile)
+                .is_ok_and(|table| field_value(&table, &format!("{os}.{field}")).is_some());
+            if in_section {
+                return format!("{current}.{os}");
+            }
+            match &profile.extends {
+                Some(parent) if profile.is_inherited(field) => current = parent,
+                _ => break,
+            }
+        }
+        current.to_string()
+    }
+
+    /// Returns where the value at `path` of the effective configuration comes from.
+    pub fn value_origin(&self, path: &[&str]) -> ConfigOrigin {
+        let Some(source) = &self.source else {
+            return ConfigOrigin::Default;
+        };
+
+        // Inherited profile fields come from the profile that sets them.
+        let owner;
+        let mut path = path.to_vec();
+        if let ["profiles", name, field @ ..] = path.as_slice()
+            && !field.is_empty()
+        {
+            owner = self.field_origin(name, &field.join("."));
+            path[1] = owner.split('.').next().unwrap_or(name);
+        }
+
+        source
+            .layers
+            .iter()
+            .rev()
+            .find(|layer| layers::has_value(&layer.table, &path, &source.os))
+            .map(|layer| layer.origin.clone())
+            .unwrap_or_else(|| {
+                if layers::has_value(&source.raw, &path, &source.os) {
+                    ConfigOrigin::File
+                } else {
+                    ConfigOrigin::Default
+                }
+            })
+    }
+
+    /// Returns the configuration with all layers, OS sections and parent profiles applied.
+    pub fn effective_table(&self) -> Result<toml::Table, ConfigError> {
+        let mut table = toml::Table::try_from(self).map_err(|_| ConfigError::FormatFailed)?;
+        if let Some(toml::Value::Table(options)) = table.get_mut("options") {
+            options.retain(|key, _| !OS_SECTIONS.contains(&key));
+        }
+        if let Some(toml::Value::Table(profiles)) = table.get_mut("profiles") {
+            for (_, profile) in profiles.iter_mut() {
+                let Some(profile) = profile.as_table_mut() else {
+                    continue;
+                };
+                profile.retain(|key, _| !OS_SECTIONS.contains(&key));
+            }
+        }
+        Ok(table)
+    }
+
+    /// The operating system the configuration is resolved for.
+    pub fn os(&self) -> &str {
+        self.source
+            .as_ref()
+            .map_or(std::env::consts::OS, |source| &source.os)
+    }
+
+    /// Names of the profiles that extend the profile `name`.
+    pub fn profile_children(&self, name: &str) -> Vec<&str> {
+        self.profiles
+            .iter()
+            .filter(|(_, profile)| profile.extends.as_deref() == Some(name))
+            .map(|(child, _)| child.as_str())
+            .collect()
+    }
+
+    /// Returns built-in editor presets together with the ones from the configuration.
+    pub fn editor_presets(&self) -> EditorPresets {
+        EditorPresets::with_custom(&self.editors)
+    }
+
+    /// Renames a profile, keeping its position. The current profile follows the rename.
+    pub fn rename_profile(&mut self, old_name: &str, new_name: &str) -> Result<(), ConfigError> {
+        if self.is_profile_exist(new_name) {
+            return Err(ConfigError::ProfileAlreadyExists(new_name.to_string()));
+        }
+        let index = self
+            .profiles
+            .get_index_of(old_name)
+            .ok_or_else(|| ConfigError::ProfileNotFound(old_name.to_string()))?;
+
+        let profile = self.profiles.shift_remove(old_name).unwrap_or_default();
+        self.profiles
+            .shift_insert(index, new_name.to_string(), profile);
+        for profile in self.profiles.values_mut() {
+            if profile.extends.as_deref() == Some(old_name) {
+                profile.extends = Some(new_name.to_string());
+            }
+        }
+        if self.options.current_profile == old_name {
+            self.options.current_profile = new_name.to_string();
+        }
+        self.copy_source_profile(old_name, new_name, true);
+        Ok(())
+    }
+
+    /// Copies a profile under a new name.
+    pub fn copy_profile(&mut self, name: &str, new_name: &str) -> Result<(), ConfigError> {
+        if self.is_profile_exist(new_name) {
+            return Err(ConfigError::ProfileAlreadyExists(new_name.to_string()));
+        }
+        let profile = self.get_profile(name)?.clone();
+        self.profiles.insert(new_name.to_string(), profile);
+        self.copy_source_profile(name, new_name, false);
+        Ok(())
+    }
+
+    /// Copies the profile in the loaded file contents, so it is written as it was written before.
+    fn copy_source_profile(&mut self, name: &str, new_name: &str, remove: bool) {
+        let Some(source) = &mut self.source else {
+            return;
+        };
+        for table in [&mut source.raw, &mut source.resolved] {
+            if let Some(toml::Value::Table(profiles)) = table.get_mut("profiles") {
+                let profile = if remove {
+                    profiles.remove(name)
+                } else {
+                    profiles.get(name).cloned()
+                };
+                if let Some(profile) = profile {
+                    profiles.insert(new_name.to_string(), profile);
+                }
+            }
+        }
+    }
+
     pub fn is_profile_exist(&self, name: &str) -> bool {
         self.profiles.contains_key(name)
     }
 
     pub fn reset(&mut self) {
-        *self = Self::default();
+        *self = Self::detected();
     }
 }
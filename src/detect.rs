@@ -0,0 +1,105 @@
+use std::{
+    env,
+    ffi::OsStr,
+    path::{Path, PathBuf},
+};
+
+use crate::{editor::EditorPresets, platform};
+
+/// Shells that Kanri looks for, in the order of preference.
+const KNOWN_SHELLS: [&str; 13] = [
+    "bash",
+    "zsh",
+    "fish",
+    "nu",
+    "pwsh",
+    "powershell",
+    "elvish",
+    "xonsh",
+    "ksh",
+    "tcsh",
+    "dash",
+    "sh",
+    "cmd",
+];
+
+/// An editor found in `PATH`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct DetectedEditor {
+    pub program: String,
+    /// Name of the editor preset of the program.
+    pub preset: String,
+    pub path: PathBuf,
+}
+
+/// A shell found in `PATH`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct DetectedShell {
+    pub program: String,
+    pub path: PathBuf,
+}
+
+/// Finds programs of editor presets in `path_var`, in the order of presets.
+pub fn detect_editors(presets: &EditorPresets, path_var: &OsStr) -> Vec<DetectedEditor> {
+    presets
+        .iter()
+        .flat_map(|(preset_name, preset)| {
+            preset.programs.iter().filter_map(move |program| {
+                platform::find_program(program, path_var).map(|path| DetectedEditor {
+                    program: program.clone(),
+                    preset: preset_name.clone(),
+                    path,
+                })
+            })
+        })
+        .collect()
+}
+
+/// Finds known shells in `path_var`.
+pub fn detect_shells(path_var: &OsStr) -> Vec<DetectedShell> {
+    let mut shells: Vec<DetectedShell> = Vec::new();
+    for program in KNOWN_SHELLS {
+        if let Some(path) = platform::find_program(program, path_var) {
+            shells.push(DetectedShell {
+                program: program.to_string(),
+                path,
+            });
+        }
+    }
+    shells
+}
+
+/// Editor for the default profile: the one from `VISUAL` or `EDITOR`, then the first editor
+/// found in `PATH`, then the platform default.
+pub fn default_editor() -> String {
+    platform::env_editor()
+        .or_else(|| {
+            let path_var = env::var_os("PATH")?;
+            detect_editors(&EditorPresets::builtin(), &path_var)
+                .into_iter()
+                .next()
+                .map(|editor| editor.program)
+        })
+        .unwrap_or_else(|| platform::fallback_editor().to_string())
+}
+
+/// Shell for the default profile: the one from the environment, then the first shell found
+/// in `PATH`, then the platform default.
+pub fn default_shell() -> String {
+    platform::env_shell()
+        .or_else(|| {
+            let path_var = env::var_os("PATH")?;
+            detect_shells(&path_var)
+                .into_iter()
+                .next()
+                .map(|shell| shell.program)
+        })
+        .unwrap_or_else(|| platform::fallback_shell().to_string())
+}
+
+/// Name of the program at `path`, used to compare shells from the environment with detected ones.
+pub fn program_stem(path: &str) -> String {
+    Path::new(path)
+        .file_stem()
+        .map_or(path.to_string(), |stem| stem.to_string_lossy().to_string())
+}
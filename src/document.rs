@@ -0,0 +1,191 @@
+use std::{fs, io::ErrorKind, path::Path};
+
+use toml_edit::{DocumentMut, InlineTable, Item, Key, TableLike};
+
+use crate::{config::ConfigError, files};
+
+/// The configuration file as a TOML document. Changes keep comments and formatting of the
+/// rest of the file.
+#[derive(Clone, Default)]
+pub struct ConfigDocument {
+    document: DocumentMut,
+}
+
+impl ConfigDocument {
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, ConfigError> {
+        let content = fs::read_to_string(path).map_err(|e| match e.kind() {
+            ErrorKind::PermissionDenied => ConfigError::ReadPermissionDenied,
+            ErrorKind::NotFound => ConfigError::FileNotFound,
+            _ => ConfigError::FileSystemError(e),
+        })?;
+        Self::parse(&content)
+    }
+
+    pub fn parse(content: &str) -> Result<Self, ConfigError> {
+        let document = content
+            .parse::<DocumentMut>()
+            .map_err(|e| ConfigError::BadConfiguration(e.to_string()))?;
+        Ok(Self { document })
+    }
+
+    pub fn as_document_mut(&mut self) -> &mut DocumentMut {
+        &mut self.document
+    }
+
+    /// Changes the document to match `table`. Values that are equal in both are not touched,
+    /// so their comments and formatting are kept.
+    pub fn sync(&mut self, table: &toml::Table) {
+        sync_table(self.document.as_table_mut(), table);
+    }
+
+    /// Sets the value at the path of keys, creating missing tables.
+    pub fn set(&mut self, keys: &[String], value: &toml::Value) -> Result<(), ConfigError> {
+        let (key, tables) = keys.split_last().ok_or_else(empty_key)?;
+
+        let mut table = self.document.as_table_mut() as &mut dyn TableLike;
+        for (index, name) in tables.iter().enumerate() {
+            if !table.contains_key(name) {
+                let mut new_table = toml_edit::Table::new();
+                // Tables that only hold other tables don't need a header.
+                new_table.set_implicit(true);
+                table.insert(name, Item::Table(new_table));
+            }
+            table = table
+                .get_mut(name)
+                .and_then(Item::as_table_like_mut)
+                .ok_or_else(|| not_a_table(&keys[..=index]))?;
+        }
+
+        set_item(table, key, value);
+        Ok(())
+    }
+
+    /// Removes the value at the path of keys. Returns `false` if it is not set.
+    pub fn unset(&mut self, keys: &[String]) -> Result<bool, ConfigError> {
+        let (key, tables) = keys.split_last().ok_or_else(empty_key)?;
+        let mut table = self.document.as_table_mut() as &mut dyn TableLike;
+        for name in tables {
+            match table.get_mut(name).and_then(Item::as_table_like_mut) {
+                Some(inner) => table = inner,
+                None => return Ok(false),
+            }
+        }
+        Ok(table.remove(key).is_some())
+    }
+
+    /// Returns the document as a TOML value.
+    pub fn to_value(&self) -> Result<toml::Value, ConfigError> {
+        toml::from_str(&self.document.to_string())
+            .map_err(|e: toml::de::Error| ConfigError::BadConfiguration(e.message().to_string()))
+    }
+
+    /// Replaces the file at `path` with the document. The previous version is kept as a `.bak`
+    /// file next to it.
+    pub fn save(&self, path: impl AsRef<Path>) -> Result<(), ConfigError> {
+        let content = self.document.to_string();
+        files::write_atomic(path.as_ref(), content.as_bytes(), true).map_err(|e| match e.kind() {
+            ErrorKind::PermissionDenied => ConfigError::WritePermissionDenied,
+            ErrorKind::NotFound => ConfigError::FileNotFound,
+            ErrorKind::StorageFull => ConfigError::StorageFull,
+            _ => ConfigError::FileSystemError(e),
+        })
+    }
+}
+
+impl std::fmt::Display for ConfigDocument {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.document)
+    }
+}
+
+fn sync_table(target: &mut dyn TableLike, table: &toml::Table) {
+    let removed: Vec<String> = target
+        .iter()
+        .map(|(key, _)| key.to_string())
+        .filter(|key| !table.contains_key(key))
+        .collect();
+    for key in removed {
+        target.remove(&key);
+    }
+
+    for (key, value) in table {
+        match (target.get_mut(key), value) {
+            (Some(item), toml::Value::Table(table)) if item.is_table_like() => {
+                if let Some(item) = item.as_table_like_mut() {
+                    sync_table(item, table);
+                }
+            }
+            (Some(item), value) if item_value(item).as_ref() == Some(value) => {}
+            _ => set_item(target, key, value),
+        }
+    }
+}
+
+/// Replaces the value under `key`. Values keep their decoration, like a comment after them.
+fn set_item(table: &mut dyn TableLike, key: &str, value: &toml::Value) {
+    match (table.get_mut(key), value) {
+        (Some(Item::Value(existing)), value) if !value.is_table() => {
+            let decor = existing.decor().clone();
+            *existing = to_edit_value(value);
+            *existing.decor_mut() = decor;
+        }
+        _ => {
+            table.insert(key, to_item(value));
+        }
+    }
+}
+
+/// Returns the value of the item, without formatting.
+fn item_value(item: &Item) -> Option<toml::Value> {
+    let mut document = DocumentMut::new();
+    document.insert("value", item.clone());
+    let mut table = document.to_string().parse::<toml::Table>().ok()?;
+    table.remove("value")
+}
+
+fn to_item(value: &toml::Value) -> Item {
+    let toml::Value::Table(table) = value else {
+        return Item::Value(to_edit_value(value));
+    };
+    let mut new_table = toml_edit::Table::new();
+    for (key, value) in table {
+        new_table.insert(key, to_item(value));
+    }
+    // Tables that only hold other tables, like `profiles`, don't need a header.
+    new_table.set_implicit(!table.is_empty() && table.values().all(toml::Value::is_table));
+    Item::Table(new_table)
+}
+
+fn to_edit_value(value: &toml::Value) -> toml_edit::Value {
+    match value {
+        toml::Value::String(value) => value.into(),
+        toml::Value::Integer(value) => (*value).into(),
+        toml::Value::Float(value) => (*value).into(),
+        toml::Value::Boolean(value) => (*value).into(),
+        toml::Value::Datetime(value) => (*value).into(),
+        toml::Value::Array(values) => values.iter().map(to_edit_value).collect(),
+        toml::Value::Table(table) => {
+            let mut inline = InlineTable::new();
+            for (key, value) in table {
+                inline.insert(key, to_edit_value(value));
+            }
+            inline.into()
+        }
+    }
+}
+
+/// Splits a dotted key, like `options.display_hidden` or `profiles."my.profile".editor`.
+pub fn parse_key(key: &str) -> Result<Vec<String>, ConfigError> {
+    let keys = Key::parse(key).map_err(|_| {
+        ConfigError::InvalidField(key.to_string(), "expected a dotted key".to_string())
+    })?;
+    Ok(keys.iter().map(|key| key.get().to_string()).collect())
+}
+
+fn empty_key() -> ConfigError {
+    ConfigError::InvalidField(String::new(), "key is empty".to_string())
+}
+
+fn not_a_table(keys: &[String]) -> ConfigError {
+    ConfigError::InvalidField(keys.join("."), "not a table".to_string())
+}
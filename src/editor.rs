@@ -0,0 +1,411 @@
+use std::path::{Path, PathBuf};
+
+use indexmap::IndexMap;
+use serde::{Deserialize, Serialize};
+
+const PLACEHOLDERS: [&str; 5] = ["{path}", "{name}", "{file}", "{line}", "{column}"];
+
+struct BuiltinPreset {
+    name: &'static str,
+    programs: &'static [&'static str],
+    args: &'static [&'static str],
+    fork_mode: bool,
+    windows_cmd: bool,
+    line_syntax: LineSyntax,
+}
+
+const BUILTIN_PRESETS: &[BuiltinPreset] = &[
+    BuiltinPreset {
+        name: "vscode",
+        programs: &[
+            "code",
+            "code-insiders",
+            "codium",
+            "codium-insiders",
+            "code-oss",
+            "cursor",
+            "windsurf",
+            "positron",
+        ],
+        args: &["."],
+        fork_mode: true,
+        windows_cmd: true,
+        line_syntax: LineSyntax::Goto,
+    },
+    BuiltinPreset {
+        name: "zed",
+        programs: &["zed", "zeditor", "zedit"],
+        args: &["."],
+        fork_mode: true,
+        windows_cmd: false,
+        line_syntax: LineSyntax::Colon,
+    },
+    BuiltinPreset {
+        name: "sublime",
+        programs: &["subl", "sublime_text"],
+        args: &["."],
+        fork_mode: true,
+        windows_cmd: false,
+        line_syntax: LineSyntax::Colon,
+    },
+    BuiltinPreset {
+        name: "lapce",
+        programs: &["lapce"],
+        args: &["."],
+        fork_mode: true,
+        windows_cmd: false,
+        line_syntax: LineSyntax::Colon,
+    },
+    BuiltinPreset {
+        name: "jetbrains",
+        programs: &[
+            "idea",
+            "idea64",
+            "pycharm",
+            "pycharm64",
+            "webstorm",
+            "webstorm64",
+            "goland",
+            "goland64",
+            "clion",
+            "clion64",
+            "rider",
+            "rider64",
+            "rustrover",
+            "rustrover64",
+            "phpstorm",
+            "phpstorm64",
+            "rubymine",
+            "rubymine64",
+            "datagrip",
+            "datagrip64",
+            "studio",
+            "studio64",
+        ],
+        args: &["."],
+        fork_mode: true,
+        windows_cmd: false,
+        line_syntax: LineSyntax::LineFlag,
+    },
+    BuiltinPreset {
+        name: "kate",
+        programs: &["kate"],
+        args: &["."],
+        fork_mode: true,
+        windows_cmd: false,
+        line_syntax: LineSyntax::ShortFlags,
+    },
+    BuiltinPreset {
+        name: "helix",
+        programs: &["hx", "helix"],
+        args: &[],
+        fork_mode: false,
+        windows_cmd: false,
+        line_syntax: LineSyntax::Colon,
+    },
+    BuiltinPreset {
+        name: "neovim",
+        programs: &["nvim", "nvim-qt", "neovide"],
+        args: &[],
+        fork_mode: false,
+        windows_cmd: false,
+        line_syntax: LineSyntax::Plus,
+    },
+    BuiltinPreset {
+        name: "vim",
+        programs: &["vim", "vi", "gvim", "mvim"],
+        args: &[],
+        fork_mode: false,
+        windows_cmd: false,
+        line_syntax: LineSyntax::Plus,
+    },
+    BuiltinPreset {
+        name: "emacs",
+        programs: &["emacs", "emacsclient"],
+        args: &[],
+        fork_mode: false,
+        windows_cmd: false,
+        line_syntax: LineSyntax::PlusColumn,
+    },
+    BuiltinPreset {
+        name: "gedit",
+        programs: &["gedit"],
+        args: &[],
+        fork_mode: true,
+        windows_cmd: false,
+        line_syntax: LineSyntax::Plus,
+    },
+    BuiltinPreset {
+        name: "terminal",
+        programs: &["nano", "micro", "kak"],
+        args: &[],
+        fork_mode: false,
+        windows_cmd: false,
+        line_syntax: LineSyntax::Plus,
+    },
+];
+
+/// Default settings for an editor: arguments, fork mode and how to open a file at a line.
+#[derive(Deserialize, Serialize, Clone, Debug, Default, PartialEq, Eq)]
+#[serde(default, deny_unknown_fields)]
+pub struct EditorPreset {
+    /// Names of programs this preset applies to, without extensions.
+    pub programs: Vec<String>,
+    pub args: Vec<String>,
+    pub fork_mode: bool,
+    /// Whether the program is a `.cmd` script on Windows.
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub windows_cmd: bool,
+    pub line_syntax: LineSyntax,
+}
+
+impl EditorPreset {
+    /// Returns the name of the program to run on the current platform.
+    pub fn platform_program(&self, program: &str) -> String {
+        if cfg!(windows) && self.windows_cmd && !program.to_lowercase().ends_with(".cmd") {
+            format!("{program}.cmd")
+        } else {
+            program.to_string()
+        }
+    }
+}
+
+impl From<&BuiltinPreset> for EditorPreset {
+    fn from(preset: &BuiltinPreset) -> Self {
+        Self {
+            programs: preset.programs.iter().map(|p| p.to_string()).collect(),
+            args: preset.args.iter().map(|a| a.to_string()).collect(),
+            fork_mode: preset.fork_mode,
+            windows_cmd: preset.windows_cmd,
+            line_syntax: preset.line_syntax,
+        }
+    }
+}
+
+/// Built-in editor presets together with the ones from the configuration.
+pub struct EditorPresets {
+    presets: IndexMap<String, EditorPreset>,
+}
+
+impl EditorPresets {
+    /// Creates the registry with only the built-in presets.
+    pub fn builtin() -> Self {
+        Self::with_custom(&IndexMap::new())
+    }
+
+    /// Creates the registry with presets from the configuration. They replace built-in presets
+    /// with the same name and take priority when looking up a program.
+    pub fn with_custom(custom: &IndexMap<String, EditorPreset>) -> Self {
+        let mut presets = custom.clone();
+        for preset in BUILTIN_PRESETS {
+            if !presets.contains_key(preset.name) {
+                presets.insert(preset.name.to_string(), preset.into());
+            }
+        }
+        Self { presets }
+    }
+
+    pub fn get(&self, name: &str) -> Option<&EditorPreset> {
+        self.presets.get(name)
+    }
+
+    /// Finds the preset for the program. Returns the name of the preset and the preset.
+    pub fn find(&self, program: &str) -> Option<(&str, &EditorPreset)> {
+        let name = program_name(program);
+        self.presets
+            .iter()
+            .find(|(_, preset)| {
+                preset
+                    .programs
+                    .iter()
+                    .any(|p| p.eq_ignore_ascii_case(&name))
+            })
+            .map(|(preset_name, preset)| (preset_name.as_str(), preset))
+    }
+
+    /// Iterates over presets, the ones from the configuration first.
+    pub fn iter(&self) -> impl Iterator<Item = (&String, &EditorPreset)> {
+        self.presets.iter()
+    }
+
+    /// Returns how the program opens a file at a line. Unknown programs get only the file.
+    pub fn line_syntax(&self, program: &str) -> LineSyntax {
+        self.find(program)
+            .map_or(LineSyntax::FileOnly, |(_, preset)| preset.line_syntax)
+    }
+}
+
+/// A file to open in the editor, optionally at a line and a column.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct FileTarget {
+    pub file: PathBuf,
+    pub line: Option<u32>,
+    pub column: Option<u32>,
+}
+
+impl FileTarget {
+    pub fn new(file: impl Into<PathBuf>) -> Self {
+        Self {
+            file: file.into(),
+            line: None,
+            column: None,
+        }
+    }
+
+    /// Parses targets like `src/main.rs`, `src/main.rs:42` and `src/main.rs:42:7`.
+    pub fn parse(value: &str) -> Self {
+        let mut file = value;
+        let mut numbers: Vec<u32> = Vec::new();
+
+        // Take at most two numbers from the end, so `C:\file` and `file:name` stay intact.
+        while numbers.len() < 2
+            && let Some((rest, number)) = file.rsplit_once(':')
+            && let Ok(number) = number.parse()
+            && !rest.is_empty()
+        {
+            numbers.insert(0, number);
+            file = rest;
+        }
+
+        Self {
+            file: PathBuf::from(file),
+            line: numbers.first().copied(),
+            column: numbers.get(1).copied(),
+        }
+    }
+
+    /// Returns the same target with the file path resolved relative to `base`.
+    pub fn resolve(&self, base: &Path) -> Self {
+        Self {
+            file: base.join(&self.file),
+            ..self.clone()
+        }
+    }
+}
+
+/// How an editor expects to be told which line of the file to open.
+#[derive(Deserialize, Serialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum LineSyntax {
+    /// `-g file:line:column`, used by VS Code and its forks.
+    Goto,
+    /// `file:line:column`.
+    Colon,
+    /// `+line file`, used by Vim and many terminal editors.
+    Plus,
+    /// `+line:column file`, used by Emacs.
+    PlusColumn,
+    /// `--line line --column column file`, used by JetBrains IDEs.
+    LineFlag,
+    /// `-l line -c column file`, used by Kate.
+    ShortFlags,
+    /// Only the file, the editor can't jump to a line.
+    #[default]
+    FileOnly,
+}
+
+impl LineSyntax {
+    /// Arguments that open the target in the editor.
+    pub fn args(&self, target: &FileTarget) -> Vec<String> {
+        let file = target.file.to_string_lossy().to_string();
+        let Some(line) = target.line else {
+            return vec![file];
+        };
+        let column = target.column;
+
+        match self {
+            LineSyntax::Goto => vec!["-g".to_string(), colon_target(&file, line, column)],
+            LineSyntax::Colon => vec![colon_target(&file, line, column)],
+            LineSyntax::Plus => vec![format!("+{line}"), file],
+            LineSyntax::PlusColumn => match column {
+                Some(column) => vec![format!("+{line}:{column}"), file],
+                None => vec![format!("+{line}"), file],
+            },
+            LineSyntax::LineFlag => {
+                let mut args = vec!["--line".to_string(), line.to_string()];
+                if let Some(column) = column {
+                    args.extend(["--column".to_string(), column.to_string()]);
+                }
+                args.push(file);
+                args
+            }
+            LineSyntax::ShortFlags => {
+                let mut args = vec!["-l".to_string(), line.to_string()];
+                if let Some(column) = column {
+                    args.extend(["-c".to_string(), column.to_string()]);
+                }
+                args.push(file);
+                args
+            }
+            LineSyntax::FileOnly => vec![file],
+        }
+    }
+}
+
+fn colon_target(file: &str, line: u32, column: Option<u32>) -> String {
+    match column {
+        Some(column) => format!("{file}:{line}:{column}"),
+        None => format!("{file}:{line}"),
+    }
+}
+
+/// Name of the program without directories and Windows extensions.
+fn program_name(program: &str) -> String {
+    let name = Path::new(program)
+        .file_name()
+        .map_or(program.into(), |name| name.to_string_lossy());
+    let name = name.to_lowercase();
+    for extension in [".cmd", ".exe", ".bat", ".sh"] {
+        if let Some(stripped) = name.strip_suffix(extension) {
+            return stripped.to_string();
+        }
+    }
+    name
+}
+
+/// Values for placeholders in program arguments.
+pub struct ArgValues<'a> {
+    /// Path to the project, or to the directory of the file that is being edited.
+    pub path: &'a Path,
+    pub name: &'a str,
+    pub target: Option<&'a FileTarget>,
+}
+
+/// Replaces placeholders in the arguments of `program`.
+///
+/// Arguments with `{file}`, `{line}` or `{column}` are skipped when there is no file to open.
+/// When there is a file but the arguments don't mention `{file}`, arguments that open the file
+/// are appended according to the [`LineSyntax`] of the program.
+pub fn expand_args(args: &[String], values: &ArgValues, syntax: LineSyntax) -> Vec<String> {
+    let path = values.path.to_string_lossy();
+    let mut uses_file = false;
+    let mut expanded = Vec::with_capacity(args.len());
+
+    for arg in args {
+        let mentions = |placeholder: &str| arg.contains(placeholder);
+        if !PLACEHOLDERS.iter().any(|placeholder| mentions(placeholder)) {
+            expanded.push(arg.clone());
+            continue;
+        }
+
+        let mut arg = arg.replace("{path}", &path).replace("{name}", values.name);
+        if ["{file}", "{line}", "{column}"].iter().any(|p| mentions(p)) {
+            let Some(target) = values.target else {
+                continue;
+            };
+            uses_file |= mentions("{file}");
+            arg = arg
+                .replace("{file}", &target.file.to_string_lossy())
+                .replace("{line}", &target.line.unwrap_or(1).to_string())
+                .replace("{column}", &target.column.unwrap_or(1).to_string());
+        }
+        expanded.push(arg);
+    }
+
+    if let Some(target) = values.target
+        && !uses_file
+    {
+        expanded.extend(syntax.args(target));
+    }
+    expanded
+}
@@ -0,0 +1,98 @@
+use std::{
+    ffi::OsString,
+    fs::{self, File, OpenOptions, TryLockError},
+    io::{self, ErrorKind, Write},
+    path::{Path, PathBuf},
+};
+
+use tempfile::Builder;
+
+/// Exclusive advisory lock for a file that is loaded, changed and saved. The lock is kept in
+/// `<file>.lock`, because the file itself is replaced on every save. It is released on drop.
+pub struct FileLock {
+    _file: File,
+}
+
+impl FileLock {
+    /// Waits until no other process holds the lock for `path` and takes it.
+    pub fn acquire(path: &Path) -> io::Result<Self> {
+        let file = Self::open(path)?;
+        file.lock()?;
+        Ok(Self { _file: file })
+    }
+
+    /// Takes the lock for `path` if no one holds it, without waiting.
+    pub fn try_acquire(path: &Path) -> io::Result<Option<Self>> {
+        let file = Self::open(path)?;
+        match file.try_lock() {
+            Ok(()) => Ok(Some(Self { _file: file })),
+            Err(TryLockError::WouldBlock) => Ok(None),
+            Err(TryLockError::Error(e)) => Err(e),
+        }
+    }
+
+    fn open(path: &Path) -> io::Result<File> {
+        let lock_path = with_suffix(path, "lock");
+        if let Some(parent) = lock_path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        OpenOptions::new()
+            .create(true)
+            .truncate(false)
+            .write(true)
+            .open(lock_path)
+    }
+}
+
+/// Replaces the file at `path` with `content`. The content goes to a temporary file in the same
+/// directory first, which is then renamed over the file, so a crash or a full disk never leave
+/// a partly written file. With `backup`, the previous version is kept as `<file>.bak`.
+pub fn write_atomic(path: &Path, content: &[u8], backup: bool) -> io::Result<()> {
+    // Replace the target of a symbolic link, not the link itself.
+    let path = fs::canonicalize(path).unwrap_or_else(|_| path.to_path_buf());
+    let metadata = match fs::metadata(&path) {
+        Ok(metadata) => Some(metadata),
+        Err(e) if e.kind() == ErrorKind::NotFound => None,
+        Err(e) => return Err(e),
+    };
+    // Renaming would replace a read-only file, which the user doesn't want to be changed.
+    if metadata
+        .as_ref()
+        .is_some_and(|m| m.permissions().readonly())
+    {
+        return Err(ErrorKind::PermissionDenied.into());
+    }
+
+    let dir = match path.parent() {
+        Some(parent) if !parent.as_os_str().is_empty() => parent,
+        _ => Path::new("."),
+    };
+    #[cfg_attr(not(unix), allow(unused_mut))]
+    let mut builder = Builder::new();
+    // New files get the permissions `fs::write` would give them, `0666` without the umask.
+    #[cfg(unix)]
+    if metadata.is_none() {
+        use std::os::unix::fs::PermissionsExt;
+        builder.permissions(fs::Permissions::from_mode(0o666));
+    }
+    let mut file = builder.tempfile_in(dir)?;
+    file.write_all(content)?;
+    file.as_file().sync_all()?;
+
+    if let Some(metadata) = metadata {
+        file.as_file().set_permissions(metadata.permissions())?;
+        if backup {
+            fs::copy(&path, with_suffix(&path, "bak"))?;
+        }
+    }
+    file.persist(&path).map_err(|e| e.error)?;
+    Ok(())
+}
+
+/// Appends a suffix to the file name, like `config.toml` to `config.toml.bak`.
+pub fn with_suffix(path: &Path, suffix: &str) -> PathBuf {
+    let mut name = path.file_name().map(OsString::from).unwrap_or_default();
+    name.push(".");
+    name.push(suffix);
+    path.with_file_name(name)
+}
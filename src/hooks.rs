@@ -0,0 +1,323 @@
+use std::{fs, io::ErrorKind, path::Path};
+
+use sha2::{Digest, Sha256};
+use thiserror::Error;
+
+use crate::{
+    blueprints::engine::BlueprintEngine,
+    config::LifecycleHook,
+    platform,
+    program::{LaunchOptions, launch_program},
+    project::{PROJECT_DIR_NAME, PROJECT_FILE_NAME, ProjectMeta},
+};
+
+const HOOKS_FILE_NAME: &str = "hooks.lua";
+
+#[derive(Debug, Error)]
+pub enum HooksError {
+    #[error("failed to read hooks: {0}.")]
+    ReadFailed(std::io::Error),
+
+    #[error("hooks are defined both in '{0}' and in the project metadata. Keep only one of them.")]
+    DefinedTwice(String),
+
+    #[error("hook '{hook}' failed: {message}")]
+    HookFailed { hook: String, message: String },
+
+    #[error("command of the '{0}' hook is empty.")]
+    EmptyCommand(String),
+}
+
+/// Events of a project that hooks can react to.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum HookEvent {
+    /// The project is being opened in the editor or in the shell.
+    Open,
+    /// A shell session is being started in the project.
+    Shell,
+}
+
+impl HookEvent {
+    /// Name of the Lua function that handles this event.
+    pub fn function_name(&self) -> &'static str {
+        match self {
+            HookEvent::Open => "on_open",
+            HookEvent::Shell => "on_shell",
+        }
+    }
+}
+
+/// Lua hooks defined by a project, either in `.kanri/hooks.lua` or in the `[hooks]` table of
+/// the project metadata.
+pub struct ProjectHooks {
+    source: String,
+    file_name: String,
+}
+
+impl ProjectHooks {
+    /// Loads hooks of the project. Returns `None` if the project has no hooks.
+    pub fn load(project_path: &Path, meta: &ProjectMeta) -> Result<Option<Self>, HooksError> {
+        let hooks_path = project_path.join(PROJECT_DIR_NAME).join(HOOKS_FILE_NAME);
+        let file_name = format!("{PROJECT_DIR_NAME}/{HOOKS_FILE_NAME}");
+
+        match fs::read_to_string(&hooks_path) {
+            Ok(source) => {
+                if !meta.hooks.is_empty() {
+                    return Err(HooksError::DefinedTwice(file_name));
+                }
+                Ok(Some(Self { source, file_name }))
+            }
+            Err(e) if e.kind() == ErrorKind::NotFound => {
+                if meta.hooks.is_empty() {
+                    return Ok(None);
+                }
+                Ok(Some(Self {
+                    source: meta.hooks.to_lua(),
+                    file_name: format!("{PROJECT_DIR_NAME}/project.toml"),
+                }))
+            }
+            Err(e) => Err(HooksError::ReadFailed(e)),
+        }
+    }
+
+    /// Lua source of the hooks.
+    pub fn source(&self) -> &str {
+        &self.source
+    }
+
+    /// Name of the file where hooks are defined, relative to the project.
+    pub fn file_name(&self) -> &str {
+        &self.file_name
+    }
+
+    /// A hash of the hooks source, used to detect when hooks change.
+    pub fn fingerprint(&self) -> String {
+        fingerprint(&self.source)
+    }
+
+    /// Runs hooks for the events in order. Events without a hook are skipped.
+    pub fn run(
+        &self,
+        events: &[HookEvent],
+        project_path: &Path,
+        project_name: &str,
+    ) -> Result<(), HooksError> {
+        let hook_error = |hook: &str, e: mlua::Error| HooksError::HookFailed {
+            hook: hook.to_string(),
+            message: e.to_string(),
+        };
+
+        let engine =
+            BlueprintEngine::init(project_path, self.file_name.as_str(), project_name, false)
+                .map_err(|e| hook_error(&self.file_name, e))?;
+        engine
+            .run(&self.source)
+            .map_err(|e| hook_error(&self.file_name, e))?;
+
+        for event in events {
+            let hook = event.function_name();
+            engine
+                .call_function(hook)
+                .map_err(|e| hook_error(hook, e))?;
+        }
+        Ok(())
+    }
+}
+
+/// Settings of a project that make Kanri run programs when the project is opened: its hooks,
+/// environment variables and the layout of its multiplexer session. Variables like `PATH` or
+/// `LD_PRELOAD` change what programs run. The user reviews and trusts them together.
+pub struct ProjectTrust {
+    hooks: Option<ProjectHooks>,
+    /// What the user reviews: a title and the text for each setting.
+    parts: Vec<(String, String)>,
+}
+
+impl ProjectTrust {
+    pub fn load(project_path: &Path, meta: &ProjectMeta) -> Result<Self, HooksError> {
+        let hooks = ProjectHooks::load(project_path, meta)?;
+        let mut parts = Vec::new();
+        if let Some(hooks) = &hooks {
+            parts.push((
+                format!("Hooks from {}", hooks.file_name),
+                hooks.source.clone(),
+            ));
+        }
+
+        if let Some(env_file) = &meta.env_file {
+            match fs::read_to_string(project_path.join(env_file)) {
+                Ok(content) => {
+                    parts.push((format!("Environment from {}", env_file.display()), content))
+                }
+                Err(e) if e.kind() == ErrorKind::NotFound => {}
+                Err(e) => return Err(HooksError::ReadFailed(e)),
+            }
+        }
+        if !meta.env.is_empty() {
+            let mut table = toml::Table::new();
+            table.insert(
+                "env".to_string(),
+                toml::Value::Table(toml::Table::try_from(&meta.env).unwrap_or_default()),
+            );
+            parts.push((
+                format!("Environment from {PROJECT_DIR_NAME}/{PROJECT_FILE_NAME}"),
+                toml::to_string(&table).unwrap_or_default(),
+            ));
+        }
+
+        if !meta.mux.is_empty() {
+            let mut table = toml::Table::new();
+            table.insert(
+                "mux".to_string(),
+                toml::Value::Table(toml::Table::try_from(&meta.mux).unwrap_or_default()),
+            );
+            parts.push((
+                format!("Session layout from {PROJECT_DIR_NAME}/{PROJECT_FILE_NAME}"),
+                toml::to_string(&table).unwrap_or_default(),
+            ));
+        }
+        if let Some(layout) = &meta.mux.zellij_layout {
+            match fs::read_to_string(project_path.join(layout)) {
+                Ok(source) => {
+                    parts.push((format!("Zellij layout from {}", layout.display()), source))
+                }
+                Err(e) if e.kind() == ErrorKind::NotFound => {}
+                Err(e) => return Err(HooksError::ReadFailed(e)),
+            }
+        }
+
+        Ok(Self { hooks, parts })
+    }
+
+    /// Hooks of the project, if it has any.
+    pub fn hooks(&self) -> Option<&ProjectHooks> {
+        self.hooks.as_ref()
+    }
+
+    /// Whether the project has nothing to trust.
+    pub fn is_empty(&self) -> bool {
+        self.parts.is_empty()
+    }
+
+    /// Titles and texts of the settings, in the order they are shown to the user.
+    pub fn parts(&self) -> &[(String, String)] {
+        &self.parts
+    }
+
+    /// A hash of all settings, used to detect when any of them change. For projects with only
+    /// hooks, it is the fingerprint of the hooks.
+    pub fn fingerprint(&self) -> String {
+        let texts: Vec<&str> = self.parts.iter().map(|(_, text)| text.as_str()).collect();
+        fingerprint(&texts.join("\n"))
+    }
+}
+
+fn fingerprint(source: &str) -> String {
+    Sha256::digest(source.as_bytes())
+        .iter()
+        .map(|byte| format!("{byte:02x}"))
+        .collect()
+}
+
+/// Project lifecycle events that global hooks from the configuration can react to.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum LifecycleEvent {
+    PostCreate,
+    PostClone,
+    PreRemove,
+    PostRename,
+    PostOpen,
+}
+
+impl LifecycleEvent {
+    pub fn name(&self) -> &'static str {
+        match self {
+            LifecycleEvent::PostCreate => "post_create",
+            LifecycleEvent::PostClone => "post_clone",
+            LifecycleEvent::PreRemove => "pre_remove",
+            LifecycleEvent::PostRename => "post_rename",
+            LifecycleEvent::PostOpen => "post_open",
+        }
+    }
+}
+
+/// Information about the project passed to lifecycle hooks.
+pub struct HookContext<'a> {
+    pub event: LifecycleEvent,
+    pub project_name: &'a str,
+    pub project_path: &'a Path,
+    /// Previous name of the project, if it was renamed.
+    pub old_name: Option<&'a str>,
+}
+
+impl HookContext<'_> {
+    /// Environment variables passed to hook commands.
+    pub fn env(&self) -> Vec<(String, String)> {
+        let mut env = vec![
+            ("KANRI_HOOK".to_string(), self.event.name().to_string()),
+            (
+                "KANRI_PROJECT_NAME".to_string(),
+                self.project_name.to_string(),
+            ),
+            (
+                "KANRI_PROJECT_PATH".to_string(),
+                self.project_path.to_string_lossy().to_string(),
+            ),
+        ];
+        if let Some(old_name) = self.old_name {
+            env.push(("KANRI_PROJECT_OLD_NAME".to_string(), old_name.to_string()));
+        }
+        env
+    }
+}
+
+/// Runs lifecycle hooks in order and stops at the first failed one.
+///
+/// Commands receive information about the project through environment variables.
+/// Lua scripts are resolved relative to the hooks directory and can use the `project` module.
+pub fn run_lifecycle_hooks(
+    hooks: &[LifecycleHook],
+    context: &HookContext,
+) -> Result<(), HooksError> {
+    let hook_name = context.event.name();
+    let hook_error = |message: String| HooksError::HookFailed {
+        hook: hook_name.to_string(),
+        message,
+    };
+
+    for hook in hooks {
+        match hook {
+            LifecycleHook::Command { command } => {
+                let (program, args) = command
+                    .split_first()
+                    .ok_or_else(|| HooksError::EmptyCommand(hook_name.to_string()))?;
+
+                let launch_options = LaunchOptions {
+                    program,
+                    args: args.to_vec(),
+                    cwd: Some(context.project_path),
+                    fork_mode: false,
+                    quiet: false,
+                    env: Some(context.env()),
+                };
+                launch_program(launch_options).map_err(|e| hook_error(e.to_string()))?;
+            }
+            LifecycleHook::Script { script } => {
+                let script_path = platform::hooks_dir().join(script);
+                let source = fs::read_to_string(&script_path).map_err(HooksError::ReadFailed)?;
+                let file_name = script.to_string_lossy().to_string();
+
+                let engine = BlueprintEngine::init(
+                    context.project_path,
+                    file_name,
+                    context.project_name,
+                    false,
+                )
+                .map_err(|e| hook_error(e.to_string()))?;
+                engine.run(&source).map_err(|e| hook_error(e.to_string()))?;
+            }
+        }
+    }
+
+    Ok(())
+}
@@ -0,0 +1,159 @@
+use std::{
+    fmt, fs,
+    io::ErrorKind,
+    path::{Path, PathBuf},
+};
+
+use crate::config::{ConfigError, parse_value};
+
+/// Name of the file with configuration for a directory and its subdirectories.
+pub const DIRECTORY_CONFIG_NAME: &str = ".kanri.toml";
+
+/// Sections a `.kanri.toml` can set. The other ones name programs that Kanri runs, and a
+/// `.kanri.toml` may come with any repository that is cloned.
+pub const DIRECTORY_SECTIONS: [&str; 2] = ["options", "autocomplete"];
+
+/// Prefix of environment variables that override configuration values.
+pub const ENV_PREFIX: &str = "KANRI_";
+
+/// Where a configuration value comes from.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ConfigOrigin {
+    Default,
+    File,
+    Directory(PathBuf),
+    Env(String),
+}
+
+impl fmt::Display for ConfigOrigin {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ConfigOrigin::Default => write!(f, "default"),
+            ConfigOrigin::File => write!(f, "config file"),
+            ConfigOrigin::Directory(path) => write!(f, "{}", path.display()),
+            ConfigOrigin::Env(name) => write!(f, "${name}"),
+        }
+    }
+}
+
+/// Configuration applied on top of the configuration file. Layers are never written back.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ConfigLayer {
+    pub origin: ConfigOrigin,
+    pub table: toml::Table,
+}
+
+impl ConfigLayer {
+    /// Loads the `.kanri.toml` file at `path`. Sections that are not in [`DIRECTORY_SECTIONS`]
+    /// are left out of the layer and returned, so the caller can warn about them.
+    pub fn load_directory(path: &Path) -> Result<(Self, Vec<String>), ConfigError> {
+        let content = fs::read_to_string(path).map_err(|e| match e.kind() {
+            ErrorKind::PermissionDenied => ConfigError::ReadPermissionDenied,
+            _ => ConfigError::FileSystemError(e),
+        })?;
+        let mut table = content.parse::<toml::Table>().map_err(|e| {
+            ConfigError::BadConfiguration(format!("{}: {}", path.display(), e.message()))
+        })?;
+        let ignored: Vec<_> = table
+            .keys()
+            .filter(|section| !DIRECTORY_SECTIONS.contains(&section.as_str()))
+            .cloned()
+            .collect();
+        table.retain(|section, _| DIRECTORY_SECTIONS.contains(&section));
+        let layer = Self {
+            origin: ConfigOrigin::Directory(path.to_path_buf()),
+            table,
+        };
+        Ok((layer, ignored))
+    }
+
+    /// Layers from `KANRI_<SECTION>_<KEY>` variables, like `KANRI_OPTIONS_DISPLAY_HIDDEN`.
+    /// `schema` has the sections and keys that can be overridden, with values of their types.
+    /// Variables that don't start with the name of a section are not overrides.
+    pub fn from_env(
+        vars: impl IntoIterator<Item = (String, String)>,
+        schema: &toml::Table,
+    ) -> Result<Vec<Self>, ConfigError> {
+        let mut vars: Vec<_> = vars
+            .into_iter()
+            .filter(|(name, _)| name.starts_with(ENV_PREFIX))
+            .collect();
+        vars.sort();
+
+        let mut layers = Vec::new();
+        for (name, value) in vars {
+            let rest = name[ENV_PREFIX.len()..].to_lowercase();
+            let Some((section, keys, key)) = schema.iter().find_map(|(section, keys)| {
+                let key = rest.strip_prefix(section.as_str())?.strip_prefix('_')?;
+                Some((section, keys.as_table()?, key))
+            }) else {
+                continue;
+            };
+
+            let value = match keys.get(key) {
+                Some(toml::Value::String(_)) => toml::Value::String(value),
+                Some(toml::Value::Boolean(_)) => match value.to_lowercase().as_str() {
+                    "true" | "1" | "yes" | "on" => toml::Value::Boolean(true),
+                    "false" | "0" | "no" | "off" => toml::Value::Boolean(false),
+                    _ => {
+                        return Err(ConfigError::BadConfiguration(format!(
+                            "{name} must be true or false, not '{value}'"
+                        )));
+                    }
+                },
+                Some(_) => parse_value(&value),
+                None => {
+                    return Err(ConfigError::BadConfiguration(format!(
+                        "{name} overrides '{section}.{key}', which does not exist"
+                    )));
+                }
+            };
+
+            let mut table = toml::Table::new();
+            table.insert(key.to_string(), value);
+            let mut layer = toml::Table::new();
+            layer.insert(section.clone(), toml::Value::Table(table));
+            layers.push(Self {
+                origin: ConfigOrigin::Env(name),
+                table: layer,
+            });
+        }
+        Ok(layers)
+    }
+}
+
+/// Returns the nearest `.kanri.toml` in `dir` or its parents.
+pub fn find_directory_config(dir: &Path) -> Option<PathBuf> {
+    dir.ancestors()
+        .map(|dir| dir.join(DIRECTORY_CONFIG_NAME))
+        .find(|path| path.is_file())
+}
+
+/// Whether the layer sets the value at `path`, directly or in the section of `os`.
+pub fn has_value(table: &toml::Table, path: &[&str], os: &str) -> bool {
+    // OS sections are inside options and inside each profile.
+    let section_at = match path.first() {
+        Some(&"options") => Some(1),
+        Some(&"profiles") => Some(2),
+        _ => None,
+    };
+    let in_section = section_at.filter(|at| *at < path.len()).map(|at| {
+        let mut path = path.to_vec();
+        path.insert(at, os);
+        path
+    });
+
+    std::iter::once(path.to_vec())
+        .chain(in_section)
+        .any(|path| lookup(table, &path).is_some())
+}
+
+/// Returns the value at the path of keys.
+pub fn lookup<'a>(table: &'a toml::Table, path: &[&str]) -> Option<&'a toml::Value> {
+    let (key, tables) = path.split_last()?;
+    let mut table = table;
+    for name in tables {
+        table = table.get(*name)?.as_table()?;
+    }
+    table.get(*key)
+}
@@ -3,11 +3,24 @@ pub mod backup;
 pub mod blueprints;
 pub mod cli;
 pub mod commands;
+pub mod completions;
 pub mod config;
+pub mod detect;
+pub mod document;
+pub mod editor;
+pub mod files;
+pub mod hooks;
+pub mod layers;
 pub mod library;
 pub mod migrations;
+pub mod mux;
 pub mod platform;
 pub mod program;
+pub mod project;
+pub mod resolve;
+pub mod session;
+pub mod shell;
+pub mod state;
 pub mod templates;
 pub mod terminal;
 
@@ -105,6 +105,19 @@ pub struct CloneOptions {
     pub name: Option<String>,
 }
 
+impl CloneOptions {
+    /// Returns the name of the directory that Git will clone the repository into.
+    pub fn directory_name(&self) -> String {
+        if let Some(name) = &self.name {
+            return name.clone();
+        }
+
+        let remote = self.remote.trim_end_matches(['/', '\\']);
+        let last = remote.rsplit(['/', '\\', ':']).next().unwrap_or(remote);
+        last.strip_suffix(".git").unwrap_or(last).to_string()
+    }
+}
+
 /// The Library struct manages a collection of projects in a specified directory.
 pub struct Library {
     projects: IndexMap<String, PathBuf>,
@@ -259,6 +272,11 @@ impl Library {
         self.projects.contains_key(name)
     }
 
+    /// Returns the path to the directory with projects.
+    pub fn base_path(&self) -> &Path {
+        &self.base_path
+    }
+
     /// Returns a vector of all project names in the library.
     pub fn get_names(&self) -> Vec<&String> {
         self.projects.keys().collect()
@@ -269,6 +287,14 @@ impl Library {
         self.projects.get(name)
     }
 
+    /// Finds the project that contains the path.
+    pub fn find_by_path(&self, path: &Path) -> Option<&str> {
+        self.projects
+            .iter()
+            .find(|(_, project_path)| path.starts_with(project_path))
+            .map(|(name, _)| name.as_str())
+    }
+
     /// Returns a reference to a map of all projects in the library.
     pub fn get_all(&self) -> &IndexMap<String, PathBuf> {
         &self.projects
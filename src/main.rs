@@ -1,19 +1,21 @@
 use std::{fs, process::exit};
 
 use anyhow::{Result, anyhow};
-use clap::Parser;
+use clap::{CommandFactory, Parser};
+use clap_complete::CompleteEnv;
 use kanri::{
     cli::{Cli, Commands, ConfigCommands, ProfilesCommands},
     commands::{blueprints, config, profiles, root},
+    completions,
     config::Config,
     platform,
-    terminal::print_error,
+    terminal::{print_error, set_verbose},
 };
 
 fn check_env() -> Result<()> {
     let config_path = platform::config_file();
     if !config_path.exists() {
-        let default_config: Config = Config::default();
+        let default_config = Config::detected();
         default_config
             .save(config_path)
             .map_err(|e| anyhow!(e.to_string()))?;
@@ -40,6 +42,11 @@ fn print_version() {
 }
 
 fn main() {
+    CompleteEnv::with_factory(Cli::command)
+        .var(completions::COMPLETE_VAR)
+        .shells(completions::SHELLS)
+        .complete();
+
     colored::control::set_override(std::env::var("NO_COLOR").is_err());
     let cli = Cli::parse();
 
@@ -47,6 +54,7 @@ fn main() {
         print_version();
         return;
     }
+    set_verbose(cli.verbose);
 
     if let Err(e) = check_env() {
         print_error(&e.to_string());
@@ -58,6 +66,10 @@ fn main() {
         Commands::Clone(args) => root::handle_clone(args),
         Commands::Open(args) => root::handle_open(args),
         Commands::List(args) => root::handle_list(args),
+        Commands::Pin(args) => root::handle_pin(args),
+        Commands::Unpin(args) => root::handle_unpin(args),
+        Commands::Info(args) => root::handle_info(args),
+        Commands::Current(args) => root::handle_current(args),
         Commands::Rename(args) => root::handle_rename(args),
         Commands::Remove(args) => root::handle_remove(args),
         Commands::Blueprints { command } => blueprints::handle(command),
@@ -66,16 +78,26 @@ fn main() {
             ConfigCommands::Edit => config::handle_edit(),
             ConfigCommands::Recent(args) => config::handle_recent(args),
             ConfigCommands::Reset => config::handle_reset(),
+            ConfigCommands::Show(args) => config::handle_show(args),
+            ConfigCommands::Get(args) => config::handle_get(args),
+            ConfigCommands::Set(args) => config::handle_set(args),
+            ConfigCommands::Unset(args) => config::handle_unset(args),
         },
         Commands::Profiles { command } => match command {
-            ProfilesCommands::New => profiles::handle_new(),
+            ProfilesCommands::New(args) => profiles::handle_new(args),
+            ProfilesCommands::Edit(args) => profiles::handle_edit(args),
+            ProfilesCommands::Rename(args) => profiles::handle_rename(args),
+            ProfilesCommands::Copy(args) => profiles::handle_copy(args),
             ProfilesCommands::Set(args) => profiles::handle_set(args),
             ProfilesCommands::Get(args) => profiles::handle_get(args),
             ProfilesCommands::List => profiles::handle_list(),
             ProfilesCommands::Remove(args) => profiles::handle_remove(args),
+            ProfilesCommands::Detect => profiles::handle_detect(),
         },
         Commands::Backup(args) => root::handle_backup(args),
         Commands::Import(args) => root::handle_import(args),
+        Commands::Init(args) => root::handle_init(args),
+        Commands::Completions(args) => root::handle_completions(args),
         Commands::Zen => root::handle_zen(),
     };
 
@@ -1,39 +1,96 @@
-use crate::config::ConfigError;
+use crate::{
+    config::{CONFIG_VERSION, ConfigError},
+    state::State,
+};
 use anyhow::Result;
+use toml_edit::{DocumentMut, Item};
 
-pub fn migrate_config(value: &mut toml::Value) -> Result<bool, ConfigError> {
-    let version = value
-        .get("version")
-        .and_then(toml::Value::as_str)
-        .unwrap_or("1");
+/// Whether the configuration document has an older version.
+pub fn needs_migration(document: &DocumentMut) -> bool {
+    document.get("version").and_then(Item::as_str) != Some(CONFIG_VERSION)
+}
+
+/// Migrates the configuration document to the current version. Migrations change the
+/// document in place, so comments and formatting are kept. Runtime data that used to be
+/// stored in the configuration is moved to `state`.
+pub fn migrate_config(document: &mut DocumentMut, state: &mut State) -> Result<bool, ConfigError> {
+    let mut was_migrated = false;
+    loop {
+        let version = document
+            .get("version")
+            .and_then(Item::as_str)
+            .unwrap_or("1");
 
-    match version {
-        "1" => {
-            migrate_v1_to_v2(value)?;
-            Ok(true)
+        match version {
+            "1" => migrate_v1_to_v2(document)?,
+            "2" => migrate_v2_to_v3(document, state)?,
+            "3" => return Ok(was_migrated),
+            _ => {
+                return Err(ConfigError::BadConfiguration(format!(
+                    "unknown configuration version: {}",
+                    version
+                )));
+            }
         }
-        "2" => Ok(false),
-        _ => Err(ConfigError::BadConfiguration(format!(
-            "unknown configuration version: {}",
-            version
-        ))),
+        was_migrated = true;
     }
 }
 
-fn migrate_v1_to_v2(value: &mut toml::Value) -> Result<(), ConfigError> {
-    let root = value.as_table_mut().ok_or_else(|| {
-        ConfigError::BadConfiguration("configuration root must be a table".to_string())
-    })?;
-
-    if let Some(profiles) = root.get_mut("profiles").and_then(toml::Value::as_table_mut) {
-        for (_, profile) in profiles {
-            if let Some(profile_table) = profile.as_table_mut() {
+fn migrate_v1_to_v2(document: &mut DocumentMut) -> Result<(), ConfigError> {
+    if let Some(profiles) = document
+        .get_mut("profiles")
+        .and_then(Item::as_table_like_mut)
+    {
+        for (_, profile) in profiles.iter_mut() {
+            if let Some(profile_table) = profile.as_table_like_mut() {
                 profile_table.remove("shell_args");
             }
         }
     }
 
-    root.insert("version".to_string(), toml::Value::String("2".to_string()));
+    set_version(document, "2");
+
+    Ok(())
+}
+
+/// Moves the recent project to the state file. A recent project already in the state is newer,
+/// since files that can't be written are migrated every time they are loaded.
+fn migrate_v2_to_v3(document: &mut DocumentMut, state: &mut State) -> Result<(), ConfigError> {
+    if let Some(recent) = document.get_mut("recent").and_then(Item::as_table_like_mut) {
+        let recent_project = recent.remove("recent_project");
+        if let Some(name) = recent_project.as_ref().and_then(Item::as_str)
+            && !name.is_empty()
+            && state.recent_project.is_empty()
+        {
+            state.recent_project = name.to_string();
+        }
+        if recent.is_empty() {
+            document.remove("recent");
+        }
+    }
+
+    set_version(document, "3");
 
     Ok(())
 }
+
+/// Sets the version, keeping its place in the document if it is there.
+fn set_version(document: &mut DocumentMut, version: &str) {
+    match document.get_mut("version") {
+        Some(Item::Value(value)) => {
+            let decor = value.decor().clone();
+            *value = version.into();
+            *value.decor_mut() = decor;
+        }
+        _ => {
+            // Put the version at the top, separated from the comments that start the file.
+            let content = format!(
+                "version = {}\n\n{document}",
+                toml_edit::Value::from(version)
+            );
+            if let Ok(new_document) = content.parse() {
+                *document = new_document;
+            }
+        }
+    }
+}
@@ -0,0 +1,271 @@
+use std::{
+    io::ErrorKind,
+    path::{Path, PathBuf},
+    process::{Command, Output, Stdio},
+};
+
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+/// Set by tmux in its sessions.
+pub const TMUX_VAR: &str = "TMUX";
+
+/// Set by zellij in its sessions.
+pub const ZELLIJ_VAR: &str = "ZELLIJ";
+
+#[derive(Debug, Error)]
+pub enum MuxError {
+    #[error("{0} is not installed or not in PATH.")]
+    NotInstalled(String),
+
+    #[error("{program} failed: {message}")]
+    CommandFailed { program: String, message: String },
+
+    #[error("failed to run {0}: {1}.")]
+    LaunchFailed(String, std::io::Error),
+
+    #[error("you are already in a zellij session. Detach from it first.")]
+    NestedZellij,
+}
+
+/// Terminal multiplexer used by `kanri open --mux`.
+#[derive(Deserialize, Serialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum Multiplexer {
+    #[default]
+    Tmux,
+    Zellij,
+}
+
+impl Multiplexer {
+    pub fn program(&self) -> &'static str {
+        match self {
+            Multiplexer::Tmux => "tmux",
+            Multiplexer::Zellij => "zellij",
+        }
+    }
+}
+
+/// Layout of the multiplexer session of a project, from `[mux]` in `.kanri/project.toml`.
+#[derive(Deserialize, Serialize, Default, Clone, Debug, PartialEq, Eq)]
+#[serde(default, deny_unknown_fields)]
+pub struct MuxLayout {
+    /// Windows of tmux sessions, in order.
+    pub windows: Vec<MuxWindow>,
+    /// Zellij layout file, relative to the project.
+    pub zellij_layout: Option<PathBuf>,
+}
+
+impl MuxLayout {
+    pub fn is_empty(&self) -> bool {
+        self.windows.is_empty() && self.zellij_layout.is_none()
+    }
+}
+
+/// A tmux window. Each command gets its own pane.
+#[derive(Deserialize, Serialize, Default, Clone, Debug, PartialEq, Eq)]
+#[serde(default, deny_unknown_fields)]
+pub struct MuxWindow {
+    pub name: Option<String>,
+    /// Directory of the window, relative to the project.
+    pub cwd: Option<PathBuf>,
+    /// Commands typed into the shells of the panes.
+    pub panes: Vec<String>,
+    /// tmux layout of the panes, like `tiled` or `main-vertical`.
+    pub layout: Option<String>,
+}
+
+/// Everything needed to create a multiplexer session for a project.
+pub struct MuxSession<'a> {
+    pub name: &'a str,
+    pub path: &'a Path,
+    /// Shell for new windows and panes. Empty to use the default one of the multiplexer.
+    pub shell: &'a str,
+    pub env: &'a [(String, String)],
+    pub layout: &'a MuxLayout,
+}
+
+/// Returns the name of the multiplexer session for the project. tmux doesn't allow `.` and `:`
+/// in session names.
+pub fn session_name(project_name: &str) -> String {
+    project_name.replace(['.', ':'], "_")
+}
+
+/// Runs tmux commands, optionally on a separate server.
+#[derive(Clone, Debug, Default)]
+pub struct Tmux {
+    /// Name of the server socket, like `tmux -L <socket>`.
+    socket: Option<String>,
+}
+
+impl Tmux {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Uses the tmux server with the socket `name` instead of the default one.
+    pub fn with_socket(name: &str) -> Self {
+        Self {
+            socket: Some(name.to_string()),
+        }
+    }
+
+    /// Returns the arguments of the tmux program for the command.
+    pub fn args(&self, command: &[String]) -> Vec<String> {
+        let mut args = Vec::new();
+        if let Some(socket) = &self.socket {
+            args.extend(["-L".to_string(), socket.clone()]);
+        }
+        args.extend(command.iter().cloned());
+        args
+    }
+
+    fn output(&self, command: &[String]) -> Result<Output, MuxError> {
+        Command::new("tmux")
+            .args(self.args(command))
+            .stdin(Stdio::null())
+            .output()
+            .map_err(|e| match e.kind() {
+                ErrorKind::NotFound => MuxError::NotInstalled("tmux".to_string()),
+                _ => MuxError::LaunchFailed("tmux".to_string(), e),
+            })
+    }
+
+    /// Runs the command and returns its output.
+    pub fn run(&self, command: &[String]) -> Result<String, MuxError> {
+        let output = self.output(command)?;
+        if !output.status.success() {
+            return Err(MuxError::CommandFailed {
+                program: "tmux".to_string(),
+                message: String::from_utf8_lossy(&output.stderr).trim().to_string(),
+            });
+        }
+        Ok(String::from_utf8_lossy(&output.stdout).to_string())
+    }
+
+    pub fn has_session(&self, name: &str) -> Result<bool, MuxError> {
+        let command = args(["has-session", "-t", &format!("={name}")]);
+        Ok(self.output(&command)?.status.success())
+    }
+
+    /// Creates the session in the background.
+    pub fn create_session(&self, session: &MuxSession) -> Result<(), MuxError> {
+        for command in create_commands(session) {
+            self.run(&command)?;
+        }
+        Ok(())
+    }
+
+    /// Arguments that attach to the session, or switch to it from inside tmux.
+    pub fn attach_args(&self, name: &str, inside_tmux: bool) -> Vec<String> {
+        let command = if inside_tmux {
+            "switch-client"
+        } else {
+            "attach-session"
+        };
+        self.args(&args([command, "-t", &format!("={name}")]))
+    }
+}
+
+fn args<const N: usize>(args: [&str; N]) -> Vec<String> {
+    args.iter().map(|arg| arg.to_string()).collect()
+}
+
+/// Returns tmux commands that create the session with its windows and panes.
+pub fn create_commands(session: &MuxSession) -> Vec<Vec<String>> {
+    let target = format!("={}", session.name);
+    let window_target = format!("={}:{{end}}", session.name);
+    let default_window = MuxWindow::default();
+    let mut windows = session.layout.windows.iter();
+    let first = windows.next().unwrap_or(&default_window);
+    let window_dir = |window: &MuxWindow| {
+        let dir = match &window.cwd {
+            Some(cwd) => session.path.join(cwd),
+            None => session.path.to_path_buf(),
+        };
+        dir.to_string_lossy().to_string()
+    };
+    let shell: Vec<String> = match session.shell {
+        "" => Vec::new(),
+        shell => vec![shell.to_string()],
+    };
+
+    let mut new_session = args(["new-session", "-d", "-s", session.name]);
+    new_session.extend(["-c".to_string(), window_dir(first)]);
+    if let Some(name) = &first.name {
+        new_session.extend(["-n".to_string(), name.clone()]);
+    }
+    for (key, value) in session.env {
+        new_session.extend(["-e".to_string(), format!("{key}={value}")]);
+    }
+    new_session.extend(shell.clone());
+
+    let mut commands = vec![new_session];
+    // New windows and panes created later by the user get the variables too.
+    for (key, value) in session.env {
+        commands.push(args(["set-environment", "-t", &target, key, value]));
+    }
+
+    for (index, window) in std::iter::once(first).chain(windows).enumerate() {
+        if index > 0 {
+            let mut new_window = args(["new-window", "-t", &format!("{target}:")]);
+            new_window.extend(["-c".to_string(), window_dir(window)]);
+            if let Some(name) = &window.name {
+                new_window.extend(["-n".to_string(), name.clone()]);
+            }
+            new_window.extend(shell.clone());
+            commands.push(new_window);
+        }
+
+        for (pane, command) in window.panes.iter().enumerate() {
+            if pane > 0 {
+                let mut split = args(["split-window", "-t", &window_target]);
+                split.extend(["-c".to_string(), window_dir(window)]);
+                split.extend(shell.clone());
+                commands.push(split);
+            }
+            commands.push(args(["send-keys", "-t", &window_target, command, "Enter"]));
+        }
+
+        if let Some(layout) = &window.layout {
+            commands.push(args(["select-layout", "-t", &window_target, layout]));
+        }
+    }
+
+    if session.layout.windows.len() > 1 {
+        commands.push(args([
+            "select-window",
+            "-t",
+            &format!("{target}:{{start}}"),
+        ]));
+    }
+    commands
+}
+
+/// Returns zellij arguments that attach to the session, or create it if `exists` is `false`.
+pub fn zellij_args(name: &str, layout: Option<&Path>, exists: bool) -> Vec<String> {
+    match layout {
+        Some(layout) if !exists => vec![
+            "--session".to_string(),
+            name.to_string(),
+            "--layout".to_string(),
+            layout.to_string_lossy().to_string(),
+        ],
+        _ => args(["attach", "--create", name]),
+    }
+}
+
+/// Whether a zellij session with the name exists.
+pub fn zellij_has_session(name: &str) -> Result<bool, MuxError> {
+    let output = Command::new("zellij")
+        .args(["list-sessions", "--short", "--no-formatting"])
+        .stdin(Stdio::null())
+        .output()
+        .map_err(|e| match e.kind() {
+            ErrorKind::NotFound => MuxError::NotInstalled("zellij".to_string()),
+            _ => MuxError::LaunchFailed("zellij".to_string(), e),
+        })?;
+    // zellij fails when there are no sessions at all.
+    let sessions = String::from_utf8_lossy(&output.stdout);
+    Ok(sessions.lines().any(|session| session.trim() == name))
+}
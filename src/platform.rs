@@ -1,13 +1,32 @@
-use std::{borrow::Cow, env, path::PathBuf};
+use std::{
+    env,
+    ffi::OsStr,
+    path::{Path, PathBuf},
+};
+
+/// Path to the configuration file, used instead of the one in the configuration directory.
+pub const CONFIG_VAR: &str = "KANRI_CONFIG";
+
+/// Directory with all files of Kanri, for portable or separate setups.
+pub const CONFIG_DIR_VAR: &str = "KANRI_CONFIG_DIR";
+
+/// Value of the variable, if it is set and not empty.
+fn env_path(name: &str) -> Option<PathBuf> {
+    env::var_os(name)
+        .filter(|value| !value.is_empty())
+        .map(PathBuf::from)
+}
 
 pub fn config_dir() -> PathBuf {
-    dir_spec::config_home()
-        .unwrap_or_else(|| PathBuf::from(".config"))
-        .join("kanri")
+    env_path(CONFIG_DIR_VAR).unwrap_or_else(|| {
+        dir_spec::config_home()
+            .unwrap_or_else(|| PathBuf::from(".config"))
+            .join("kanri")
+    })
 }
 
 pub fn config_file() -> PathBuf {
-    config_dir().join("config.toml")
+    env_path(CONFIG_VAR).unwrap_or_else(|| config_dir().join("config.toml"))
 }
 
 pub fn templates_file() -> PathBuf {
@@ -18,51 +37,112 @@ pub fn blueprints_dir() -> PathBuf {
     config_dir().join("blueprints")
 }
 
-pub fn default_editor() -> Cow<'static, str> {
-    if let Ok(v) = env::var("VISUAL").or_else(|_| env::var("EDITOR")) {
-        return Cow::Owned(v);
-    }
-    #[cfg(target_os = "windows")]
-    {
-        Cow::Borrowed("code.cmd")
-    }
-    #[cfg(target_os = "macos")]
-    {
-        Cow::Borrowed("code")
-    }
-    #[cfg(all(not(target_os = "windows"), not(target_os = "macos")))]
-    {
-        Cow::Borrowed("nvim")
+pub fn hooks_dir() -> PathBuf {
+    config_dir().join("hooks")
+}
+
+pub fn state_dir() -> PathBuf {
+    // A separate setup doesn't share history and trusted hooks with the main one.
+    if let Some(dir) = env_path(CONFIG_DIR_VAR) {
+        return dir.join("state");
     }
+    dir_spec::state_home()
+        .unwrap_or_else(|| PathBuf::from(".local/state"))
+        .join("kanri")
+}
+
+pub fn state_file() -> PathBuf {
+    state_dir().join("state.json")
+}
+
+pub fn sessions_dir() -> PathBuf {
+    state_dir().join("sessions")
 }
 
-pub fn default_shell() -> Cow<'static, str> {
-    if let Ok(v) = env::var("SHELL") {
-        return Cow::Owned(v);
+/// Editor set in `VISUAL` or `EDITOR`.
+pub fn env_editor() -> Option<String> {
+    ["VISUAL", "EDITOR"]
+        .iter()
+        .filter_map(|name| env::var(name).ok())
+        .find(|value| !value.is_empty())
+}
+
+/// Editor used when no editor is set and none is found in `PATH`.
+pub fn fallback_editor() -> &'static str {
+    if cfg!(target_os = "windows") {
+        "code.cmd"
+    } else if cfg!(target_os = "macos") {
+        "code"
+    } else {
+        "nvim"
     }
-    #[cfg(target_os = "windows")]
-    {
-        if let Ok(v) = env::var("COMSPEC") {
-            return Cow::Owned(v);
-        }
+}
 
-        Cow::Borrowed("powershell.exe")
+/// Shell set in `SHELL`, or in `COMSPEC` on Windows.
+pub fn env_shell() -> Option<String> {
+    let mut names = vec!["SHELL"];
+    if cfg!(target_os = "windows") {
+        names.push("COMSPEC");
     }
+    names
+        .iter()
+        .filter_map(|name| env::var(name).ok())
+        .find(|value| !value.is_empty())
+}
 
-    #[cfg(not(target_os = "windows"))]
-    {
-        #[cfg(target_os = "macos")]
-        {
-            Cow::Borrowed("zsh")
-        }
-
-        #[cfg(not(target_os = "macos"))]
-        {
-            Cow::Borrowed("bash")
-        }
+/// Shell used when no shell is set and none is found in `PATH`.
+pub fn fallback_shell() -> &'static str {
+    if cfg!(target_os = "windows") {
+        "powershell.exe"
+    } else if cfg!(target_os = "macos") {
+        "zsh"
+    } else {
+        "bash"
     }
 }
 
+/// Looks for an executable program in the directories of `path_var`, which has
+/// the format of the `PATH` variable.
+pub fn find_program(name: &str, path_var: &OsStr) -> Option<PathBuf> {
+    env::split_paths(path_var).find_map(|dir| {
+        program_candidates(name)
+            .into_iter()
+            .map(|candidate| dir.join(candidate))
+            .find(|path| is_executable(path))
+    })
+}
+
+#[cfg(windows)]
+fn program_candidates(name: &str) -> Vec<String> {
+    let extensions = env::var("PATHEXT").unwrap_or_else(|_| ".COM;.EXE;.BAT;.CMD".to_string());
+    let mut candidates = vec![name.to_string()];
+    candidates.extend(
+        extensions
+            .split(';')
+            .filter(|extension| !extension.is_empty())
+            .map(|extension| format!("{name}{}", extension.to_lowercase())),
+    );
+    candidates
+}
+
+#[cfg(not(windows))]
+fn program_candidates(name: &str) -> Vec<String> {
+    vec![name.to_string()]
+}
+
+#[cfg(unix)]
+pub fn is_executable(path: &Path) -> bool {
+    use std::os::unix::fs::PermissionsExt;
+
+    path.metadata()
+        .is_ok_and(|metadata| metadata.is_file() && metadata.permissions().mode() & 0o111 != 0)
+}
+
+#[cfg(not(unix))]
+pub fn is_executable(path: &Path) -> bool {
+    path.is_file()
+}
+
 pub fn default_projects_dir() -> PathBuf {
     dir_spec::home()
         .unwrap_or_else(|| PathBuf::from("."))
@@ -1,11 +1,14 @@
 use anyhow::Result;
 use std::{
+    ffi::OsString,
     io::ErrorKind,
     path::Path,
     process::{Command, Stdio},
 };
 use thiserror::Error;
 
+use crate::platform;
+
 #[derive(Debug, Error)]
 pub enum ProgramError {
     #[error("Program not found: {0}")]
@@ -34,6 +37,34 @@ pub struct LaunchOptions<'a> {
     pub env: Option<Vec<(String, String)>>,
 }
 
+/// Checks that the program can be started: it is an executable file or a program in `PATH`.
+/// `PATH` from the environment of the options is used if it is set there.
+pub fn check_program(options: &LaunchOptions) -> Result<(), ProgramError> {
+    let program = Path::new(options.program);
+    let found = if program.components().count() > 1 {
+        // Relative paths may be resolved from either working directory.
+        platform::is_executable(program)
+            || options
+                .cwd
+                .is_some_and(|cwd| platform::is_executable(&cwd.join(program)))
+    } else {
+        let path_var = options
+            .env
+            .as_ref()
+            .and_then(|env| env.iter().find(|(name, _)| name == "PATH"))
+            .map(|(_, value)| OsString::from(value))
+            .or_else(|| std::env::var_os("PATH"))
+            .unwrap_or_default();
+        platform::find_program(options.program, &path_var).is_some()
+    };
+
+    if found {
+        Ok(())
+    } else {
+        Err(ProgramError::ProgramNotFound(options.program.to_string()))
+    }
+}
+
 pub fn launch_program(options: LaunchOptions) -> Result<(), ProgramError> {
     let mut cmd = Command::new(options.program);
 
@@ -0,0 +1,217 @@
+use std::{
+    fs,
+    io::ErrorKind,
+    path::{Path, PathBuf},
+};
+
+use indexmap::IndexMap;
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+use crate::mux::MuxLayout;
+
+/// Directory inside a project where Kanri keeps per-project files.
+pub const PROJECT_DIR_NAME: &str = ".kanri";
+
+pub const PROJECT_FILE_NAME: &str = "project.toml";
+
+const PROJECT_KINDS: [(&str, &str); 14] = [
+    ("Cargo.toml", "rust"),
+    ("go.mod", "go"),
+    ("package.json", "node"),
+    ("deno.json", "deno"),
+    ("pyproject.toml", "python"),
+    ("requirements.txt", "python"),
+    ("pom.xml", "java"),
+    ("build.gradle", "java"),
+    ("build.gradle.kts", "kotlin"),
+    ("Gemfile", "ruby"),
+    ("composer.json", "php"),
+    ("mix.exs", "elixir"),
+    ("CMakeLists.txt", "cmake"),
+    ("Makefile", "make"),
+];
+
+#[derive(Debug, Error)]
+pub enum ProjectError {
+    #[error("failed to read project metadata: {0}.")]
+    ReadFailed(std::io::Error),
+
+    #[error("project metadata is invalid: {0}.")]
+    BadMetadata(String),
+
+    #[error("failed to read environment file '{0}': {1}.")]
+    EnvFileReadFailed(String, std::io::Error),
+
+    #[error("line {line} of environment file '{file}' is invalid: {message}.")]
+    BadEnvFile {
+        file: String,
+        line: usize,
+        message: String,
+    },
+}
+
+/// Per-project settings stored in `.kanri/project.toml` inside the project directory.
+#[derive(Deserialize, Serialize, Default, Clone)]
+#[serde(default, deny_unknown_fields)]
+pub struct ProjectMeta {
+    pub tags: Vec<String>,
+    /// Profile used to open the project instead of the current one.
+    pub profile: Option<String>,
+    /// Environment variables for the editor and shell sessions.
+    pub env: IndexMap<String, String>,
+    /// File with environment variables in the `.env` format, relative to the project.
+    pub env_file: Option<PathBuf>,
+    pub hooks: HooksTable,
+    /// Windows of the session opened by `kanri open --mux`.
+    pub mux: MuxLayout,
+}
+
+/// Hooks written inline in the project metadata. Each value is Lua code.
+#[derive(Deserialize, Serialize, Default, Clone)]
+#[serde(default, deny_unknown_fields)]
+pub struct HooksTable {
+    pub on_open: Option<String>,
+    pub on_shell: Option<String>,
+}
+
+impl HooksTable {
+    pub fn is_empty(&self) -> bool {
+        self.on_open.is_none() && self.on_shell.is_none()
+    }
+
+    /// Converts hooks to Lua source with a function for each hook.
+    pub fn to_lua(&self) -> String {
+        let mut source = String::new();
+        for (name, code) in [("on_open", &self.on_open), ("on_shell", &self.on_shell)] {
+            if let Some(code) = code {
+                source.push_str(&format!("function {name}()\n{code}\nend\n"));
+            }
+        }
+        source
+    }
+}
+
+impl ProjectMeta {
+    /// Loads metadata of the project. Projects without metadata get the default one.
+    pub fn load(project_path: &Path) -> Result<Self, ProjectError> {
+        let content = match fs::read_to_string(Self::path(project_path)) {
+            Ok(content) => content,
+            Err(e) if e.kind() == ErrorKind::NotFound => return Ok(Self::default()),
+            Err(e) => return Err(ProjectError::ReadFailed(e)),
+        };
+
+        toml::from_str(&content).map_err(|e| ProjectError::BadMetadata(e.to_string()))
+    }
+
+    /// Collects environment variables of the project. Variables from the `env` table
+    /// override the ones from the environment file. A missing environment file is ignored.
+    pub fn load_env(&self, project_path: &Path) -> Result<IndexMap<String, String>, ProjectError> {
+        let mut env = IndexMap::new();
+
+        if let Some(env_file) = &self.env_file {
+            let file_name = env_file.to_string_lossy().to_string();
+            match fs::read_to_string(project_path.join(env_file)) {
+                Ok(content) => env.extend(parse_env_file(&content, &file_name)?),
+                Err(e) if e.kind() == ErrorKind::NotFound => {}
+                Err(e) => return Err(ProjectError::EnvFileReadFailed(file_name, e)),
+            }
+        }
+
+        env.extend(self.env.clone());
+        Ok(env)
+    }
+
+    /// Returns the path to the metadata file of the project.
+    pub fn path(project_path: &Path) -> PathBuf {
+        project_path.join(PROJECT_DIR_NAME).join(PROJECT_FILE_NAME)
+    }
+}
+
+/// Guesses the kind of the project by looking at well-known files in its root.
+pub fn detect_kind(project_path: &Path) -> Option<&'static str> {
+    PROJECT_KINDS
+        .iter()
+        .find(|(marker, _)| project_path.join(marker).is_file())
+        .map(|(_, kind)| *kind)
+}
+
+/// Parses variables from a file in the `.env` format.
+///
+/// Every line is `KEY=VALUE`, optionally prefixed with `export` like in `.envrc` files.
+/// Values can be quoted. Empty lines and lines starting with `#` are skipped.
+pub fn parse_env_file(
+    content: &str,
+    file_name: &str,
+) -> Result<Vec<(String, String)>, ProjectError> {
+    let mut variables = Vec::new();
+
+    for (index, line) in content.lines().enumerate() {
+        let bad_line = |message: &str| ProjectError::BadEnvFile {
+            file: file_name.to_string(),
+            line: index + 1,
+            message: message.to_string(),
+        };
+
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let line = line.strip_prefix("export ").unwrap_or(line);
+
+        let (key, value) = line
+            .split_once('=')
+            .ok_or_else(|| bad_line("expected KEY=VALUE"))?;
+        let key = key.trim();
+        let valid_key = key
+            .chars()
+            .next()
+            .is_some_and(|c| c.is_ascii_alphabetic() || c == '_')
+            && key.chars().all(|c| c.is_ascii_alphanumeric() || c == '_');
+        if !valid_key {
+            return Err(bad_line("invalid variable name"));
+        }
+
+        variables.push((
+            key.to_string(),
+            parse_env_value(value.trim()).ok_or_else(|| bad_line("unterminated quote"))?,
+        ));
+    }
+
+    Ok(variables)
+}
+
+fn parse_env_value(value: &str) -> Option<String> {
+    match value.chars().next() {
+        Some(quote @ ('"' | '\'')) => {
+            let inner = value[1..].strip_suffix(quote)?;
+            if quote == '\'' {
+                return Some(inner.to_string());
+            }
+
+            let mut result = String::new();
+            let mut chars = inner.chars();
+            while let Some(c) = chars.next() {
+                if c != '\\' {
+                    result.push(c);
+                    continue;
+                }
+                match chars.next() {
+                    Some('n') => result.push('\n'),
+                    Some('t') => result.push('\t'),
+                    Some(other) => result.push(other),
+                    None => result.push('\\'),
+                }
+            }
+            Some(result)
+        }
+        // Unquoted values end at an inline comment.
+        _ => Some(
+            value
+                .split_once(" #")
+                .map_or(value, |(value, _)| value)
+                .trim_end()
+                .to_string(),
+        ),
+    }
+}
@@ -0,0 +1,201 @@
+use crate::{config::ConfigError, layers::DIRECTORY_SECTIONS};
+
+/// Names of sections with settings for one operating system, as in `std::env::consts::OS`.
+pub const OS_SECTIONS: [&str; 3] = ["linux", "macos", "windows"];
+
+/// Applies the section of the operating system `os` to the table. The section stays in the
+/// table, so it is written back on save.
+pub fn apply_os_section(table: &mut toml::Table, os: &str) {
+    if let Some(toml::Value::Table(section)) = table.get(os).cloned() {
+        merge_table(table, section);
+    }
+}
+
+/// Merges `overrides` into `table`. Nested tables are merged by key, other values are replaced.
+fn merge_table(table: &mut toml::Table, overrides: toml::Table) {
+    for (key, value) in overrides {
+        match (table.get_mut(&key), value) {
+            (Some(toml::Value::Table(table)), toml::Value::Table(value)) => table.extend(value),
+            (_, value) => {
+                table.insert(key, value);
+            }
+        }
+    }
+}
+
+/// Merges a `.kanri.toml` layer on top of `table`. Only the sections in [`DIRECTORY_SECTIONS`]
+/// are merged. Options of the layer override the ones from the OS section of `table`.
+pub fn merge_layer(table: &mut toml::Table, layer: &toml::Table, os: &str) {
+    let sections = layer
+        .iter()
+        .filter(|(key, _)| DIRECTORY_SECTIONS.contains(&key.as_str()));
+    for (key, value) in sections {
+        let mut value = value.clone();
+        match (key.as_str(), table.get_mut(key), &mut value) {
+            ("options", Some(toml::Value::Table(options)), toml::Value::Table(fields)) => {
+                merge_options(options, fields, os);
+            }
+            (
+                "autocomplete",
+                Some(toml::Value::Table(autocomplete)),
+                toml::Value::Table(fields),
+            ) => {
+                autocomplete.extend(fields.clone());
+            }
+            _ => {
+                table.insert(key.clone(), value);
+            }
+        }
+    }
+}
+
+/// Merges options from a layer. Fields of the layer replace the ones in the OS section of
+/// `table`, so the section doesn't override them when it is applied.
+fn merge_options(options: &mut toml::Table, fields: &mut toml::Table, os: &str) {
+    apply_os_section(fields, os);
+    fields.retain(|key, _| !OS_SECTIONS.contains(&key));
+
+    if let Some(toml::Value::Table(section)) = options.get_mut(os) {
+        section.retain(|key, _| !fields.contains_key(key));
+    }
+    merge_table(options, fields.clone());
+}
+
+/// Applies OS sections of profiles and merges profiles with `extends` into their parents.
+/// Returns the names of the merged profiles, parents first.
+pub fn resolve_profiles(value: &mut toml::Value, os: &str) -> Result<Vec<String>, ConfigError> {
+    let Some(profiles) = value
+        .get_mut("profiles")
+        .and_then(toml::Value::as_table_mut)
+    else {
+        return Ok(Vec::new());
+    };
+
+    let raw = profiles.clone();
+    let mut order = Vec::new();
+    for name in raw.keys() {
+        resolve_profile(name, &raw, profiles, os, &mut Vec::new(), &mut order)?;
+    }
+    Ok(order)
+}
+
+fn resolve_profile(
+    name: &str,
+    raw: &toml::Table,
+    resolved: &mut toml::Table,
+    os: &str,
+    chain: &mut Vec<String>,
+    order: &mut Vec<String>,
+) -> Result<(), ConfigError> {
+    if order.iter().any(|resolved| resolved == name) {
+        return Ok(());
+    }
+    if chain.iter().any(|child| child == name) {
+        chain.push(name.to_string());
+        return Err(ConfigError::BadConfiguration(format!(
+            "profiles extend each other in a cycle: {}",
+            chain.join(" -> ")
+        )));
+    }
+    let Some(profile) = raw.get(name).and_then(toml::Value::as_table) else {
+        // Let deserialization report what is wrong with it.
+        return Ok(());
+    };
+
+    let mut profile = profile.clone();
+    apply_os_section(&mut profile, os);
+    match profile.get("extends") {
+        None => {}
+        Some(toml::Value::String(parent)) => {
+            if !raw.contains_key(parent) {
+                return Err(ConfigError::BadConfiguration(format!(
+                    "profile '{name}' extends '{parent}', which does not exist"
+                )));
+            }
+            chain.push(name.to_string());
+            resolve_profile(parent, raw, resolved, os, chain, order)?;
+            chain.pop();
+
+            let mut merged = resolved
+                .get(parent)
+                .and_then(toml::Value::as_table)
+                .cloned()
+                .unwrap_or_default();
+            merged.retain(|key, _| key != "extends" && !OS_SECTIONS.contains(&key));
+            merge_table(&mut merged, profile);
+            profile = merged;
+        }
+        Some(_) => {
+            return Err(ConfigError::BadConfiguration(format!(
+                "'extends' of profile '{name}' must be the name of a profile"
+            )));
+        }
+    }
+
+    resolved.insert(name.to_string(), toml::Value::Table(profile));
+    order.push(name.to_string());
+    Ok(())
+}
+
+/// Writes values that changed from `old` to `new` into `raw`, the table as it was written in
+/// the file. Unchanged values keep their place in `raw`, even if they were resolved from
+/// other places.
+///
+/// Values that are set in the section of `os` are changed in that section. Values that
+/// became equal to the `inherited` ones are removed, so they are inherited again.
+pub fn apply_changes(
+    raw: &mut toml::Table,
+    old: &toml::Table,
+    new: &toml::Table,
+    os: Option<&str>,
+    inherited: Option<&toml::Table>,
+) {
+    for (key, value) in new {
+        let old_value = old.get(key);
+        if old_value == Some(value) {
+            continue;
+        }
+
+        let in_section = os.filter(|os| {
+            raw.get(*os)
+                .and_then(toml::Value::as_table)
+                .is_some_and(|section| section.contains_key(key))
+        });
+        let target = match in_section.and_then(|os| raw.get_mut(os)) {
+            Some(toml::Value::Table(section)) => section,
+            _ => &mut *raw,
+        };
+        let inherited = inherited.and_then(|inherited| inherited.get(key));
+
+        match (old_value, value) {
+            (Some(toml::Value::Table(old_table)), toml::Value::Table(table)) => {
+                let entry = target
+                    .entry(key.clone())
+                    .or_insert_with(|| toml::Value::Table(toml::Table::new()));
+                if !entry.is_table() {
+                    *entry = toml::Value::Table(toml::Table::new());
+                }
+                if let toml::Value::Table(entry) = entry {
+                    let inherited = inherited.and_then(toml::Value::as_table);
+                    apply_changes(entry, old_table, table, None, inherited);
+                    if entry.is_empty() {
+                        target.remove(key);
+                    }
+                }
+            }
+            _ if in_section.is_none() && inherited == Some(value) => {
+                target.remove(key);
+            }
+            _ => {
+                target.insert(key.clone(), value.clone());
+            }
+        }
+    }
+
+    for key in old.keys().filter(|key| !new.contains_key(*key)) {
+        raw.remove(key);
+        if let Some(toml::Value::Table(section)) = os.and_then(|os| raw.get_mut(os)) {
+            section.remove(key);
+        }
+    }
+}
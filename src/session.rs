@@ -0,0 +1,133 @@
+use std::{
+    fs,
+    io::ErrorKind,
+    path::{Path, PathBuf},
+};
+
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+/// Set to `1` in shell sessions started by Kanri.
+pub const SESSION_VAR: &str = "KANRI_SESSION";
+
+/// How many Kanri shell sessions are nested, starting from 1.
+pub const SESSION_DEPTH_VAR: &str = "KANRI_SESSION_DEPTH";
+
+/// Identifier of the Kanri process that started the shell session.
+pub const SESSION_ID_VAR: &str = "KANRI_SESSION_ID";
+
+/// Set by the shell integration when it runs Kanri, so Kanri knows the shell can exit on request.
+pub const INTEGRATION_VAR: &str = "KANRI_SHELL_INTEGRATION";
+
+/// Exit code that asks the shell integration to exit the current shell session.
+pub const REPLACE_EXIT_CODE: i32 = 88;
+
+#[derive(Debug, Error)]
+pub enum SessionError {
+    #[error("failed to read the session handoff: {0}.")]
+    ReadFailed(std::io::Error),
+
+    #[error("failed to write the session handoff: {0}.")]
+    WriteFailed(std::io::Error),
+
+    #[error("session handoff is corrupted: {0}.")]
+    BadHandoff(String),
+}
+
+/// What Kanri should do when a shell session is opened inside another one.
+#[derive(Deserialize, Serialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum NestedSessionMode {
+    /// Offer to replace the current session with the new one.
+    #[default]
+    Replace,
+    /// Warn and start a nested session.
+    Warn,
+    /// Refuse to start a nested session.
+    Refuse,
+}
+
+/// A shell session started by Kanri.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Session {
+    pub depth: u32,
+    pub id: Option<u32>,
+    /// Name of the project the session belongs to.
+    pub project_name: Option<String>,
+    pub project_path: Option<PathBuf>,
+}
+
+impl Session {
+    /// Returns the session the current process runs in, if any.
+    pub fn current() -> Option<Self> {
+        Self::from_env(|name| std::env::var(name).ok())
+    }
+
+    /// Reads the session from environment variables provided by `lookup`.
+    pub fn from_env(lookup: impl Fn(&str) -> Option<String>) -> Option<Self> {
+        lookup(SESSION_VAR).filter(|value| !value.is_empty())?;
+
+        Some(Self {
+            // Sessions started by older versions have no depth.
+            depth: lookup(SESSION_DEPTH_VAR)
+                .and_then(|value| value.parse().ok())
+                .unwrap_or(1),
+            id: lookup(SESSION_ID_VAR).and_then(|value| value.parse().ok()),
+            project_name: lookup("KANRI_PROJECT_NAME").filter(|value| !value.is_empty()),
+            project_path: lookup("KANRI_PROJECT_PATH")
+                .filter(|value| !value.is_empty())
+                .map(PathBuf::from),
+        })
+    }
+}
+
+/// Environment variables for a new shell session started by the process with `session_id`.
+/// Sessions without an id can't be replaced, because no Kanri process waits for them.
+pub fn session_env(parent: Option<&Session>, session_id: Option<u32>) -> Vec<(String, String)> {
+    let depth = parent.map_or(0, |session| session.depth) + 1;
+    let session_id = session_id.map(|id| id.to_string()).unwrap_or_default();
+    vec![
+        (SESSION_VAR.to_string(), "1".to_string()),
+        (SESSION_DEPTH_VAR.to_string(), depth.to_string()),
+        (SESSION_ID_VAR.to_string(), session_id),
+    ]
+}
+
+/// A request to the Kanri process that started a shell session to open another project
+/// after the session ends.
+#[derive(Deserialize, Serialize, Clone, Debug, PartialEq, Eq)]
+pub struct Handoff {
+    pub project_name: String,
+    pub profile: Option<String>,
+    pub no_hooks: bool,
+}
+
+impl Handoff {
+    /// Returns the path to the handoff file of the session.
+    pub fn path(sessions_dir: &Path, session_id: u32) -> PathBuf {
+        sessions_dir.join(format!("{session_id}.json"))
+    }
+
+    pub fn save(&self, path: &Path) -> Result<(), SessionError> {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent).map_err(SessionError::WriteFailed)?;
+        }
+        let content =
+            serde_json::to_string(self).map_err(|e| SessionError::BadHandoff(e.to_string()))?;
+        fs::write(path, content).map_err(SessionError::WriteFailed)
+    }
+
+    /// Reads and removes the handoff. Returns `None` if there is no handoff.
+    pub fn take(path: &Path) -> Result<Option<Self>, SessionError> {
+        let content = match fs::read_to_string(path) {
+            Ok(content) => content,
+            Err(e) if e.kind() == ErrorKind::NotFound => return Ok(None),
+            Err(e) => return Err(SessionError::ReadFailed(e)),
+        };
+        fs::remove_file(path).map_err(SessionError::WriteFailed)?;
+
+        serde_json::from_str(&content)
+            .map(Some)
+            .map_err(|e| SessionError::BadHandoff(e.to_string()))
+    }
+}
@@ -0,0 +1,240 @@
+use std::path::Path;
+
+use clap::ValueEnum;
+
+use crate::session;
+
+/// Shells supported by the shell integration.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, ValueEnum)]
+pub enum Shell {
+    Bash,
+    Zsh,
+    Fish,
+    Powershell,
+    Nushell,
+}
+
+const BASH_SCRIPT: &str = r#"# Kanri shell integration for bash.
+# Add the following line to your ~/.bashrc:
+#   eval "$(kanri init bash)"
+# To show the current project in your prompt:
+#   PS1='$(kanri_prompt)'"$PS1"
+
+kanri() {
+    KANRI_SHELL_INTEGRATION=1 command kanri "$@"
+    local kanri_status=$?
+    # Kanri asks to leave the session when it is replaced by another one.
+    if [ "$kanri_status" -eq {replace_code} ] && [ -n "$KANRI_SESSION" ]; then
+        exit 0
+    fi
+    return "$kanri_status"
+}
+
+kcd() {
+    local target
+    target="$(command kanri open --path "$@")" || return
+    cd -- "$target" || return
+}
+
+kanri_project() {
+    local base={base}
+    case "$PWD/" in
+        "$base"/?*)
+            local relative="${PWD#"$base"/}"
+            printf '%s\n' "${relative%%/*}"
+            ;;
+    esac
+}
+
+kanri_prompt() {
+    local project
+    project="$(kanri_project)"
+    if [ -n "$project" ]; then
+        printf '[%s] ' "$project"
+    fi
+}
+"#;
+
+const ZSH_SCRIPT: &str = r#"# Kanri shell integration for zsh.
+# Add the following line to your ~/.zshrc:
+#   eval "$(kanri init zsh)"
+# To show the current project in your prompt:
+#   setopt PROMPT_SUBST
+#   PROMPT='$(kanri_prompt)'"$PROMPT"
+
+kanri() {
+    KANRI_SHELL_INTEGRATION=1 command kanri "$@"
+    local kanri_status=$?
+    # Kanri asks to leave the session when it is replaced by another one.
+    if [ "$kanri_status" -eq {replace_code} ] && [ -n "$KANRI_SESSION" ]; then
+        exit 0
+    fi
+    return "$kanri_status"
+}
+
+kcd() {
+    local target
+    target="$(command kanri open --path "$@")" || return
+    cd -- "$target" || return
+}
+
+kanri_project() {
+    local base={base}
+    case "$PWD/" in
+        "$base"/?*)
+            local relative="${PWD#"$base"/}"
+            printf '%s\n' "${relative%%/*}"
+            ;;
+    esac
+}
+
+kanri_prompt() {
+    local project
+    project="$(kanri_project)"
+    if [[ -n "$project" ]]; then
+        printf '[%s] ' "$project"
+    fi
+}
+"#;
+
+const FISH_SCRIPT: &str = r#"# Kanri shell integration for fish.
+# Add the following line to your ~/.config/fish/config.fish:
+#   kanri init fish | source
+# To show the current project in your prompt, call `kanri_prompt` in your fish_prompt function.
+
+function kanri --description 'Run Kanri and leave the session when it is replaced'
+    KANRI_SHELL_INTEGRATION=1 command kanri $argv
+    set -l kanri_status $status
+    # Kanri asks to leave the session when it is replaced by another one.
+    if test $kanri_status -eq {replace_code}; and set -q KANRI_SESSION
+        exit 0
+    end
+    return $kanri_status
+end
+
+function kcd --description 'Change directory to a Kanri project'
+    set -l target (command kanri open --path $argv); or return
+    cd $target
+end
+
+function kanri_project --description 'Print the name of the current Kanri project'
+    set -l base {base}
+    if string match -q -- "$base/?*" "$PWD/"
+        set -l relative (string replace -- "$base/" '' "$PWD")
+        string split -m 1 -f 1 / -- $relative
+    end
+end
+
+function kanri_prompt --description 'Print the current Kanri project for the prompt'
+    set -l project (kanri_project)
+    if test -n "$project"
+        printf '[%s] ' $project
+    end
+end
+"#;
+
+const POWERSHELL_SCRIPT: &str = r#"# Kanri shell integration for PowerShell.
+# Add the following line to your $PROFILE:
+#   Invoke-Expression (& kanri init powershell | Out-String)
+# To show the current project in your prompt, call `kanri_prompt` in your prompt function.
+
+function kanri {
+    $env:KANRI_SHELL_INTEGRATION = '1'
+    try {
+        & (Get-Command kanri -CommandType Application -TotalCount 1) @args
+    } finally {
+        Remove-Item Env:KANRI_SHELL_INTEGRATION -ErrorAction SilentlyContinue
+    }
+    # Kanri asks to leave the session when it is replaced by another one.
+    if ($LASTEXITCODE -eq {replace_code} -and $env:KANRI_SESSION) { exit }
+}
+
+function kcd {
+    $target = & (Get-Command kanri -CommandType Application -TotalCount 1) open --path @args
+    if ($LASTEXITCODE -ne 0) { return }
+    Set-Location -LiteralPath $target
+}
+
+function kanri_project {
+    $base = {base}
+    $separator = [System.IO.Path]::DirectorySeparatorChar
+    $current = (Get-Location).ProviderPath
+    if ($current.StartsWith($base + $separator, [System.StringComparison]::OrdinalIgnoreCase)) {
+        $current.Substring($base.Length + 1).Split($separator)[0]
+    }
+}
+
+function kanri_prompt {
+    $project = kanri_project
+    if ($project) { "[$project] " }
+}
+"#;
+
+const NUSHELL_SCRIPT: &str = r#"# Kanri shell integration for Nushell.
+# Save the output to a file and source it in your config.nu:
+#   kanri init nushell | save -f ~/.config/nushell/kanri.nu
+#   source ~/.config/nushell/kanri.nu
+# To show the current project in your prompt, call `kanri_prompt` in $env.PROMPT_COMMAND.
+
+def --wrapped kanri [...args] {
+    with-env { KANRI_SHELL_INTEGRATION: "1" } { do -i { ^kanri ...$args } }
+    # Kanri asks to leave the session when it is replaced by another one.
+    if $env.LAST_EXIT_CODE == {replace_code} and ($env.KANRI_SESSION? | is-not-empty) { exit }
+}
+
+def --env kcd [...args: string] {
+    let target = (^kanri open --path ...$args | str trim)
+    cd $target
+}
+
+def kanri_project [] {
+    let base = {base}
+    let relative = (try { $env.PWD | path relative-to $base } catch { "" })
+    if ($relative | is-empty) { "" } else { $relative | path split | first }
+}
+
+def kanri_prompt [] {
+    let project = (kanri_project)
+    if ($project | is-empty) { "" } else { $"[($project)] " }
+}
+"#;
+
+/// Generates the integration script for the shell. The projects directory is used by
+/// the prompt helpers to find out which project the current directory belongs to.
+pub fn init_script(shell: Shell, projects_directory: &Path) -> String {
+    let base = projects_directory.to_string_lossy();
+    let base = base.trim_end_matches(['/', '\\']);
+
+    let (template, quoted) = match shell {
+        Shell::Bash => (BASH_SCRIPT, quote_posix(base)),
+        Shell::Zsh => (ZSH_SCRIPT, quote_posix(base)),
+        Shell::Fish => (FISH_SCRIPT, quote_fish(base)),
+        Shell::Powershell => (POWERSHELL_SCRIPT, quote_powershell(base)),
+        Shell::Nushell => (NUSHELL_SCRIPT, quote_nushell(base)),
+    };
+
+    template
+        .replace("{base}", &quoted)
+        .replace("{replace_code}", &session::REPLACE_EXIT_CODE.to_string())
+}
+
+fn quote_posix(value: &str) -> String {
+    format!("'{}'", value.replace('\'', r"'\''"))
+}
+
+fn quote_fish(value: &str) -> String {
+    format!("'{}'", value.replace('\\', r"\\").replace('\'', r"\'"))
+}
+
+fn quote_powershell(value: &str) -> String {
+    format!("'{}'", value.replace('\'', "''"))
+}
+
+fn quote_nushell(value: &str) -> String {
+    // Raw strings can't contain their own terminator, so make the delimiter long enough.
+    let mut hashes = String::from("#");
+    while value.contains(&format!("'{hashes}")) {
+        hashes.push('#');
+    }
+    format!("r{hashes}'{value}'{hashes}")
+}
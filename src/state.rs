@@ -0,0 +1,189 @@
+use std::{
+    fs,
+    io::ErrorKind,
+    path::Path,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use indexmap::IndexMap;
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+use crate::files::{self, FileLock};
+
+#[derive(Debug, Error)]
+pub enum StateError {
+    #[error("failed to format state to JSON.")]
+    FormatFailed,
+
+    #[error("state file is corrupted: {0}.")]
+    BadState(String),
+
+    #[error("cannot write to the state file.")]
+    WritePermissionDenied,
+
+    #[error("cannot lock the state file: {0}.")]
+    LockFailed(std::io::Error),
+
+    #[error("file system error occurred: {0}.")]
+    FileSystemError(#[from] std::io::Error),
+}
+
+/// Information about how a project was used.
+#[derive(Deserialize, Serialize, Default, Clone, Debug, PartialEq, Eq)]
+#[serde(default)]
+pub struct HistoryEntry {
+    /// Unix timestamp of the last time the project was opened.
+    pub last_opened: u64,
+    pub open_count: u64,
+}
+
+/// Runtime data that Kanri manages by itself, as opposed to the user configuration.
+#[derive(Deserialize, Serialize, Default, Clone)]
+#[serde(default)]
+pub struct State {
+    pub history: IndexMap<String, HistoryEntry>,
+    /// Names of pinned projects in the order they were pinned.
+    pub pins: Vec<String>,
+    /// Fingerprints of project hooks that the user has trusted, by project path.
+    pub trusted_hooks: IndexMap<String, String>,
+    /// Name of the most recently opened project, opened by `kanri open -`.
+    pub recent_project: String,
+}
+
+impl State {
+    /// Loads state from the file. A missing file results in an empty state.
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, StateError> {
+        let content = match fs::read_to_string(path) {
+            Ok(content) => content,
+            Err(e) if e.kind() == ErrorKind::NotFound => return Ok(Self::default()),
+            Err(e) => return Err(StateError::FileSystemError(e)),
+        };
+
+        serde_json::from_str(&content).map_err(|e| StateError::BadState(e.to_string()))
+    }
+
+    /// Locks the state file at `path` until the lock is dropped. Take the lock before loading
+    /// the state to change it, so changes made by other processes at the same time are kept.
+    pub fn lock(path: impl AsRef<Path>) -> Result<FileLock, StateError> {
+        FileLock::acquire(path.as_ref()).map_err(StateError::LockFailed)
+    }
+
+    pub fn save(&self, path: impl AsRef<Path>) -> Result<(), StateError> {
+        let path = path.as_ref();
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        let content = serde_json::to_string_pretty(self).map_err(|_| StateError::FormatFailed)?;
+        files::write_atomic(path, content.as_bytes(), false).map_err(|e| match e.kind() {
+            ErrorKind::PermissionDenied => StateError::WritePermissionDenied,
+            _ => StateError::FileSystemError(e),
+        })
+    }
+
+    /// Records that a project has been opened just now.
+    pub fn record_open(&mut self, name: &str) {
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_secs())
+            .unwrap_or_default();
+        let entry = self.history.entry(name.to_string()).or_default();
+        entry.last_opened = now;
+        entry.open_count += 1;
+    }
+
+    /// Pins a project. Returns `false` if it was already pinned.
+    pub fn pin(&mut self, name: &str) -> bool {
+        if self.is_pinned(name) {
+            return false;
+        }
+        self.pins.push(name.to_string());
+        true
+    }
+
+    /// Unpins a project. Returns `false` if it wasn't pinned.
+    pub fn unpin(&mut self, name: &str) -> bool {
+        let length = self.pins.len();
+        self.pins.retain(|pin| pin != name);
+        self.pins.len() != length
+    }
+
+    pub fn is_pinned(&self, name: &str) -> bool {
+        self.pins.iter().any(|pin| pin == name)
+    }
+
+    /// Returns the position of the pinned project, starting from 1.
+    pub fn pin_position(&self, name: &str) -> Option<usize> {
+        self.pins.iter().position(|pin| pin == name).map(|i| i + 1)
+    }
+
+    /// Returns the pinned project at the given position, starting from 1.
+    pub fn get_pin(&self, position: usize) -> Option<&str> {
+        position
+            .checked_sub(1)
+            .and_then(|i| self.pins.get(i))
+            .map(|pin| pin.as_str())
+    }
+
+    /// Checks if the user has trusted exactly these hooks of the project.
+    pub fn is_hook_trusted(&self, project_path: &Path, fingerprint: &str) -> bool {
+        self.trusted_hooks
+            .get(project_path.to_string_lossy().as_ref())
+            .is_some_and(|trusted| trusted == fingerprint)
+    }
+
+    /// Remembers that the user trusts these hooks of the project.
+    pub fn trust_hook(&mut self, project_path: &Path, fingerprint: &str) {
+        self.trusted_hooks.insert(
+            project_path.to_string_lossy().to_string(),
+            fingerprint.to_string(),
+        );
+    }
+
+    /// Moves all records of a project to a new name and path.
+    pub fn rename_project(
+        &mut self,
+        old_name: &str,
+        new_name: &str,
+        old_path: &Path,
+        new_path: &Path,
+    ) {
+        if let Some(fingerprint) = self
+            .trusted_hooks
+            .shift_remove(old_path.to_string_lossy().as_ref())
+        {
+            self.trust_hook(new_path, &fingerprint);
+        }
+        if let Some(entry) = self.history.shift_remove(old_name) {
+            self.history.insert(new_name.to_string(), entry);
+        }
+        for pin in self.pins.iter_mut().filter(|pin| *pin == old_name) {
+            *pin = new_name.to_string();
+        }
+        if self.recent_project == old_name {
+            self.recent_project = new_name.to_string();
+        }
+    }
+
+    /// Forgets everything about a project.
+    pub fn remove_project(&mut self, name: &str) {
+        self.history.shift_remove(name);
+        self.unpin(name);
+        if self.recent_project == name {
+            self.recent_project.clear();
+        }
+    }
+
+    /// Sorts project names so pinned projects come first in the pin order, followed by
+    /// the most recently opened ones. Projects that were never opened keep their original order.
+    pub fn rank_projects<'a>(&self, names: &[&'a str]) -> Vec<&'a str> {
+        let mut ranked = names.to_vec();
+        ranked.sort_by_key(|name| {
+            (
+                self.pin_position(name).unwrap_or(usize::MAX),
+                std::cmp::Reverse(self.history.get(*name).map_or(0, |entry| entry.last_opened)),
+            )
+        });
+        ranked
+    }
+}
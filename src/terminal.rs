@@ -1,9 +1,12 @@
-use std::io::IsTerminal;
+use std::{
+    io::IsTerminal,
+    sync::atomic::{AtomicBool, Ordering},
+};
 
 use anyhow::Result;
 use colored::Colorize;
 use dialoguer::{
-    Confirm, Input, Select,
+    Confirm, FuzzySelect, Input, MultiSelect, Select,
     console::{Style, style},
     theme::{ColorfulTheme, Theme},
 };
@@ -16,10 +19,28 @@ pub enum TerminalError {
     InteractionFailed,
 }
 
+static VERBOSE: AtomicBool = AtomicBool::new(false);
+
+/// Enables messages printed with [`print_verbose`].
+pub fn set_verbose(verbose: bool) {
+    VERBOSE.store(verbose, Ordering::Relaxed);
+}
+
+/// Prints a message only if Kanri runs with `--verbose`.
+pub fn print_verbose(msg: &str) {
+    if VERBOSE.load(Ordering::Relaxed) {
+        eprintln!(" {}", msg.dimmed());
+    }
+}
+
 pub fn print_error(msg: &str) {
     eprintln!(" {}: {msg}", "Error".bright_red().bold());
 }
 
+pub fn print_warning(msg: &str) {
+    eprintln!(" {}: {msg}", "Warning".bright_yellow().bold());
+}
+
 pub fn print_done(msg: &str) {
     println!(" {} {msg}", "✓".bold().bright_green())
 }
@@ -69,11 +90,34 @@ pub fn ask_string_dialog(question: &str, report: bool) -> Result<String, Termina
         .map_err(|_| TerminalError::InteractionFailed)
 }
 
-pub fn ask_select(items: &Vec<String>, report: bool) -> Result<usize, TerminalError> {
+pub fn ask_select(question: &str, items: &[String], report: bool) -> Result<usize, TerminalError> {
     Select::with_theme(&get_dialog_theme())
-        .with_prompt("Which template to use?")
+        .with_prompt(question)
+        .default(0)
+        .items(items)
+        .report(report)
+        .interact()
+        .map_err(|_| TerminalError::InteractionFailed)
+}
+
+pub fn ask_multi_select(question: &str, items: &[String]) -> Result<Vec<usize>, TerminalError> {
+    MultiSelect::with_theme(&get_dialog_theme())
+        .with_prompt(question)
+        .items(items)
+        .interact()
+        .map_err(|_| TerminalError::InteractionFailed)
+}
+
+pub fn ask_fuzzy_select(
+    question: &str,
+    items: &[String],
+    report: bool,
+) -> Result<usize, TerminalError> {
+    FuzzySelect::with_theme(&get_dialog_theme())
+        .with_prompt(question)
         .default(0)
         .items(items)
+        .max_length(15)
         .report(report)
         .interact()
         .map_err(|_| TerminalError::InteractionFailed)
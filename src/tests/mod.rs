@@ -1,6 +1,22 @@
 mod test_autocomplete;
 mod test_blueprints;
+mod test_completions;
+mod test_config;
+#[cfg(unix)]
+mod test_detect;
+mod test_document;
+mod test_editor;
+mod test_files;
+mod test_hooks;
+mod test_layers;
 mod test_library;
+mod test_migrations;
+#[cfg(unix)]
+mod test_mux;
+mod test_project;
+mod test_session;
+mod test_shell;
+mod test_state;
 
 use std::path::Path;
 use tempfile::TempDir;
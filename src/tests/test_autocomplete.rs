@@ -1,4 +1,6 @@
-use crate::autocomplete::{CompletionResult, suggest_completion};
+use crate::autocomplete::{
+    CompletionResult, MatchKind, edit_distance, score_match, suggest_completion,
+};
 
 #[test]
 pub fn test_autocomplete_found_similar() {
@@ -26,3 +28,87 @@ pub fn test_autocomplete_nothing() {
     let result = suggest_completion("enjo", &words);
     assert_eq!(result, CompletionResult::Nothing)
 }
+
+#[test]
+pub fn test_autocomplete_ambiguous() {
+    let words: Vec<&str> = vec!["kanri", "kanri-docs", "orange"];
+
+    let result = suggest_completion("kan", &words);
+    assert_eq!(
+        result,
+        CompletionResult::Ambiguous(vec![String::from("kanri"), String::from("kanri-docs")])
+    )
+}
+
+#[test]
+pub fn test_autocomplete_prefers_stronger_match() {
+    let words: Vec<&str> = vec!["my-app", "apple"];
+
+    let result = suggest_completion("app", &words);
+    assert_eq!(
+        result,
+        CompletionResult::FoundSimilar(String::from("apple"))
+    )
+}
+
+#[test]
+pub fn test_autocomplete_case_insensitive() {
+    let words: Vec<&str> = vec!["Kanri", "kanri-docs"];
+
+    let result = suggest_completion("kanri", &words);
+    assert_eq!(
+        result,
+        CompletionResult::FoundSimilar(String::from("Kanri"))
+    )
+}
+
+#[test]
+pub fn test_autocomplete_subsequence_and_typo() {
+    let words: Vec<&str> = vec!["watermelon", "orange"];
+
+    assert_eq!(
+        suggest_completion("wtrmln", &words),
+        CompletionResult::FoundSimilar(String::from("watermelon"))
+    );
+    assert_eq!(
+        suggest_completion("ornage", &words),
+        CompletionResult::FoundSimilar(String::from("orange"))
+    );
+}
+
+#[test]
+pub fn test_score_match_kinds() {
+    assert_eq!(score_match("app", "apple").unwrap().kind, MatchKind::Prefix);
+    assert_eq!(
+        score_match("ple", "apple").unwrap().kind,
+        MatchKind::Substring
+    );
+    assert_eq!(
+        score_match("ape", "apple").unwrap().kind,
+        MatchKind::Subsequence
+    );
+    assert_eq!(score_match("apxle", "apple").unwrap().kind, MatchKind::Typo);
+    assert!(score_match("xyz", "apple").is_none());
+}
+
+#[test]
+pub fn test_score_match_short_input_typo() {
+    assert!(score_match("x", "kit").is_none());
+    assert!(score_match("xy", "ab").is_none());
+    assert_eq!(
+        suggest_completion("x", &["kit", "api"]),
+        CompletionResult::Nothing
+    );
+    assert_eq!(
+        score_match("bta", "beta").unwrap().kind,
+        MatchKind::Subsequence
+    );
+    assert_eq!(score_match("bxta", "beta").unwrap().kind, MatchKind::Typo);
+}
+
+#[test]
+pub fn test_edit_distance() {
+    assert_eq!(edit_distance("kitten", "sitting"), 3);
+    assert_eq!(edit_distance("", "abc"), 3);
+    assert_eq!(edit_distance("same", "same"), 0);
+}
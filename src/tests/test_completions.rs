@@ -0,0 +1,48 @@
+use std::ffi::OsString;
+
+use clap::CommandFactory;
+use clap_complete::env::EnvCompleter;
+
+use crate::{
+    cli::Cli,
+    completions::{Nushell, SHELLS, write_registration},
+    shell::Shell,
+};
+
+fn complete(args: &[&str]) -> String {
+    let mut cmd = Cli::command();
+    cmd.build();
+    let args: Vec<OsString> = args.iter().map(OsString::from).collect();
+
+    let mut buf = Vec::new();
+    Nushell
+        .write_complete(&mut cmd, args, None, &mut buf)
+        .unwrap();
+    String::from_utf8(buf).unwrap()
+}
+
+#[test]
+fn test_completions_subcommands() {
+    let output = complete(&["kanri", "pro"]);
+    assert_eq!(output, "profiles\tManage your profiles\n");
+}
+
+#[test]
+fn test_completions_flags() {
+    let output = complete(&["kanri", "open", "--sh"]);
+    assert!(output.starts_with("--shell\t"));
+}
+
+#[test]
+fn test_completions_shells() {
+    for name in ["bash", "zsh", "fish", "powershell", "nushell", "nu"] {
+        assert!(SHELLS.completer(name).is_some(), "{name} is not supported");
+    }
+}
+
+#[test]
+fn test_completions_nushell_registration() {
+    let mut buf = Vec::new();
+    write_registration(Shell::Nushell, &mut buf).unwrap();
+    insta::assert_snapshot!(String::from_utf8(buf).unwrap());
+}
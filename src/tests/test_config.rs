@@ -0,0 +1,453 @@
+use std::path::{Path, PathBuf};
+
+use crate::{
+    config::{Config, ConfigError, Launcher, Profile, parse_value},
+    editor::{ArgValues, FileTarget, LineSyntax, expand_args},
+    mux::Multiplexer,
+    platform,
+};
+
+const LAUNCHERS_CONFIG: &str = r#"
+[profiles.default]
+editor = "nvim"
+shell = "bash"
+
+[profiles.default.launchers]
+git = "lazygit"
+test = ["cargo", "watch", "-x", "test"]
+idea = { program = "idea", args = ["."], fork_mode = true, env = { JAVA_HOME = "/opt/jdk" } }
+"#;
+
+#[test]
+fn test_config_launchers() {
+    let config: Config = toml::from_str(LAUNCHERS_CONFIG).unwrap();
+    let launchers = &config.get_profile("default").unwrap().launchers;
+
+    let git = &launchers["git"];
+    assert_eq!(git.program(), "lazygit");
+    assert!(git.args().is_empty());
+    assert!(!git.fork_mode());
+
+    let test = &launchers["test"];
+    assert_eq!(test.program(), "cargo");
+    assert_eq!(test.args(), ["watch", "-x", "test"]);
+
+    let idea = &launchers["idea"];
+    assert_eq!(idea.program(), "idea");
+    assert_eq!(idea.args(), ["."]);
+    assert!(idea.fork_mode());
+    assert_eq!(
+        idea.env()
+            .and_then(|env| env.get("JAVA_HOME"))
+            .map(String::as_str),
+        Some("/opt/jdk")
+    );
+}
+
+#[test]
+fn test_config_launcher_takes_file() {
+    let content = LAUNCHERS_CONFIG.replace(r#"args = ["."]"#, r#"args = ["--open={file}:{line}"]"#);
+    let config: Config = toml::from_str(&content).unwrap();
+    let launchers = &config.get_profile("default").unwrap().launchers;
+    assert!(!launchers["git"].takes_file());
+    assert!(!launchers["test"].takes_file());
+
+    let idea = &launchers["idea"];
+    assert!(idea.takes_file());
+    let target = FileTarget {
+        file: PathBuf::from("/work/app/src/main.rs"),
+        line: Some(12),
+        column: None,
+    };
+    let values = ArgValues {
+        path: Path::new("/work/app"),
+        name: "app",
+        target: Some(&target),
+    };
+    // The file goes where the launcher puts it, nothing is appended.
+    assert_eq!(
+        expand_args(idea.args(), &values, LineSyntax::Goto),
+        ["--open=/work/app/src/main.rs:12"]
+    );
+}
+
+#[test]
+fn test_config_launchers_keep_format() {
+    let config: Config = toml::from_str(LAUNCHERS_CONFIG).unwrap();
+    let content = toml::to_string(&config).unwrap();
+    let config: Config = toml::from_str(&content).unwrap();
+    let launchers = &config.get_profile("default").unwrap().launchers;
+
+    assert!(matches!(launchers["git"], Launcher::Program(_)));
+    assert!(matches!(launchers["test"], Launcher::Command(_)));
+    assert!(matches!(launchers["idea"], Launcher::Detailed(_)));
+}
+
+#[test]
+fn test_config_launcher_unknown_field() {
+    let content = LAUNCHERS_CONFIG.replace("fork_mode = true", "forked = true");
+    assert!(toml::from_str::<Config>(&content).is_err());
+}
+
+#[test]
+fn test_config_editor_list() {
+    let content = LAUNCHERS_CONFIG.replace(r#"editor = "nvim""#, r#"editor = ["cursor", "nvim"]"#);
+    let config: Config = toml::from_str(&content).unwrap();
+    let editor = &config.get_profile("default").unwrap().editor;
+    assert_eq!(editor.as_slice(), ["cursor", "nvim"]);
+
+    let content = toml::to_string(&config).unwrap();
+    assert!(content.contains(r#"editor = ["cursor", "nvim"]"#));
+}
+
+#[test]
+fn test_parse_value() {
+    assert_eq!(parse_value("code"), toml::Value::String("code".to_string()));
+    assert_eq!(
+        parse_value("\"a b\""),
+        toml::Value::String("a b".to_string())
+    );
+    assert_eq!(parse_value("true"), toml::Value::Boolean(true));
+    assert_eq!(parse_value("42"), toml::Value::Integer(42));
+    assert_eq!(
+        parse_value(r#"[".", "-n"]"#),
+        toml::Value::Array(vec![".".into(), "-n".into()])
+    );
+}
+
+#[test]
+fn test_profile_set_field() {
+    let mut profile = Profile::default();
+
+    profile.set_field("editor", parse_value("hx")).unwrap();
+    profile
+        .set_field("editor_args", parse_value(r#"["."]"#))
+        .unwrap();
+    profile
+        .set_field("editor_fork_mode", parse_value("true"))
+        .unwrap();
+    profile
+        .set_field("env.RUST_LOG", parse_value("debug"))
+        .unwrap();
+
+    assert_eq!(profile.editor, "hx".into());
+    assert_eq!(profile.editor_args, vec![".".to_string()]);
+    assert!(profile.editor_fork_mode);
+    assert_eq!(profile.env["RUST_LOG"], "debug");
+
+    profile.unset_field("env.RUST_LOG").unwrap();
+    assert!(profile.env.is_empty());
+}
+
+#[test]
+fn test_profile_set_field_invalid() {
+    let mut profile = Profile::default();
+
+    assert!(matches!(
+        profile.set_field("editr", parse_value("hx")),
+        Err(ConfigError::InvalidField(..))
+    ));
+    assert!(matches!(
+        profile.set_field("editor_fork_mode", parse_value("yes")),
+        Err(ConfigError::InvalidField(..))
+    ));
+    assert!(matches!(
+        profile.set_field("editor.name", parse_value("hx")),
+        Err(ConfigError::InvalidField(..))
+    ));
+    assert!(matches!(
+        profile.unset_field("env.MISSING"),
+        Err(ConfigError::InvalidField(..))
+    ));
+}
+
+#[test]
+fn test_profile_parse_field_value() {
+    let profile = Profile::default();
+    let parse = |field: &str, value: &str| profile.parse_field_value(field, value);
+
+    assert_eq!(parse("shell", "true"), "true".into());
+    assert_eq!(parse("shell", "123"), "123".into());
+    assert_eq!(parse("extends", "123"), "123".into());
+    assert_eq!(parse("env.PORT", "8080"), "8080".into());
+    assert_eq!(parse("linux.shell", "true"), "true".into());
+    assert_eq!(parse("editor_fork_mode", "true"), true.into());
+    assert_eq!(
+        parse("editor", r#"["hx", "nvim"]"#),
+        toml::Value::Array(vec!["hx".into(), "nvim".into()])
+    );
+
+    let mut profile = Profile::default();
+    let value = profile.parse_field_value("shell", "123");
+    profile.set_field("shell", value).unwrap();
+    assert_eq!(profile.shell, "123");
+}
+
+#[test]
+fn test_config_rename_profile() {
+    let mut config = Config::default();
+    config.copy_profile("default", "work").unwrap();
+    config.copy_profile("default", "home").unwrap();
+
+    config.rename_profile("default", "main").unwrap();
+    let names: Vec<&String> = config.profiles.keys().collect();
+    assert_eq!(names, vec!["main", "work", "home"]);
+    assert_eq!(config.options.current_profile, "main");
+
+    assert!(matches!(
+        config.rename_profile("work", "home"),
+        Err(ConfigError::ProfileAlreadyExists(_))
+    ));
+    assert!(matches!(
+        config.rename_profile("missing", "other"),
+        Err(ConfigError::ProfileNotFound(_))
+    ));
+}
+
+const EXTENDS_CONFIG: &str = r#"
+[profiles.default]
+editor = "code"
+editor_args = ["."]
+editor_fork_mode = true
+shell = "bash"
+terminal = ["alacritty", "-e", "{shell}"]
+env = { RUST_LOG = "info", EDITOR = "code" }
+
+[profiles.work]
+extends = "default"
+shell = "zsh"
+multiplexer = "zellij"
+env = { RUST_LOG = "debug" }
+
+[profiles.terminal]
+extends = "work"
+editor = "nvim"
+editor_args = []
+editor_fork_mode = false
+"#;
+
+fn parse_config(content: &str) -> Result<Config, ConfigError> {
+    parse_config_for_os(content, "linux")
+}
+
+fn parse_config_for_os(content: &str, os: &str) -> Result<Config, ConfigError> {
+    Config::from_value_for_os(toml::from_str(content).unwrap(), os)
+}
+
+#[test]
+fn test_config_extends() {
+    let config = parse_config(EXTENDS_CONFIG).unwrap();
+
+    let work = config.get_profile("work").unwrap();
+    assert_eq!(work.editor, "code".into());
+    assert_eq!(work.editor_args, vec![".".to_string()]);
+    assert!(work.editor_fork_mode);
+    assert_eq!(work.shell, "zsh");
+    assert_eq!(work.env["RUST_LOG"], "debug");
+    assert_eq!(work.env["EDITOR"], "code");
+
+    let terminal = config.get_profile("terminal").unwrap();
+    assert_eq!(terminal.extends.as_deref(), Some("work"));
+    assert_eq!(terminal.editor, "nvim".into());
+    assert!(terminal.editor_args.is_empty());
+    assert!(!terminal.editor_fork_mode);
+    assert_eq!(terminal.shell, "zsh");
+    assert_eq!(terminal.env["RUST_LOG"], "debug");
+    assert_eq!(terminal.terminal, ["alacritty", "-e", "{shell}"]);
+    assert_eq!(terminal.multiplexer, Some(Multiplexer::Zellij));
+}
+
+#[test]
+fn test_config_extends_origin() {
+    let config = parse_config(EXTENDS_CONFIG).unwrap();
+
+    assert_eq!(config.field_origin("terminal", "editor"), "terminal");
+    assert_eq!(config.field_origin("terminal", "shell"), "work");
+    assert_eq!(config.field_origin("terminal", "editor_args"), "terminal");
+    assert_eq!(config.field_origin("terminal", "env.RUST_LOG"), "work");
+    assert_eq!(config.field_origin("terminal", "env.EDITOR"), "default");
+    assert_eq!(config.field_origin("default", "shell"), "default");
+    assert_eq!(config.field_origin("terminal", "terminal"), "default");
+    assert_eq!(config.field_origin("terminal", "multiplexer"), "work");
+}
+
+#[test]
+fn test_config_extends_save_changed_child() {
+    let mut config = parse_config(EXTENDS_CONFIG).unwrap();
+    let work = config.profiles.get_mut("work").unwrap();
+    work.set_field("editor_fork_mode", parse_value("false"))
+        .unwrap();
+    work.unset_field("shell").unwrap();
+
+    let saved = config.to_table().unwrap();
+    let work = saved["profiles"]["work"].as_table().unwrap();
+    assert_eq!(
+        work.get("editor_fork_mode"),
+        Some(&toml::Value::Boolean(false))
+    );
+    assert_eq!(work.get("shell"), None);
+    assert_eq!(work.get("editor"), None);
+}
+
+#[test]
+fn test_config_extends_save() {
+    let mut config = parse_config(EXTENDS_CONFIG).unwrap();
+    config
+        .profiles
+        .get_mut("default")
+        .unwrap()
+        .set_field("editor", parse_value("zed"))
+        .unwrap();
+
+    let saved = config.to_table().unwrap();
+    let work = saved["profiles"]["work"].as_table().unwrap();
+    assert_eq!(
+        work.keys().collect::<Vec<_>>(),
+        ["extends", "shell", "multiplexer", "env"]
+    );
+    assert_eq!(work["env"].as_table().unwrap().len(), 1);
+
+    // The change of the parent reaches the profiles that extend it.
+    let config = Config::from_value(toml::Value::Table(saved)).unwrap();
+    assert_eq!(config.get_profile("work").unwrap().editor, "zed".into());
+    assert_eq!(
+        config.get_profile("terminal").unwrap().editor,
+        "nvim".into()
+    );
+}
+
+#[test]
+fn test_config_extends_unset_field() {
+    let mut config = parse_config(EXTENDS_CONFIG).unwrap();
+    let work = config.profiles.get_mut("work").unwrap();
+
+    work.unset_field("shell").unwrap();
+    assert_eq!(work.shell, "bash");
+    work.unset_field("env.RUST_LOG").unwrap();
+    assert_eq!(work.env["RUST_LOG"], "info");
+    assert!(work.is_inherited("shell"));
+}
+
+#[test]
+fn test_config_extends_errors() {
+    let missing = EXTENDS_CONFIG.replace(r#"extends = "default""#, r#"extends = "missing""#);
+    assert!(matches!(
+        parse_config(&missing),
+        Err(ConfigError::BadConfiguration(message)) if message.contains("'missing'")
+    ));
+
+    let cycle = EXTENDS_CONFIG.replace(r#"extends = "default""#, r#"extends = "terminal""#);
+    assert!(matches!(
+        parse_config(&cycle),
+        Err(ConfigError::BadConfiguration(message)) if message.contains("cycle")
+    ));
+
+    let itself = EXTENDS_CONFIG.replace(r#"extends = "default""#, r#"extends = "work""#);
+    assert!(parse_config(&itself).is_err());
+}
+
+#[test]
+fn test_config_extends_rename() {
+    let mut config = parse_config(EXTENDS_CONFIG).unwrap();
+    config.rename_profile("work", "office").unwrap();
+
+    assert_eq!(config.profile_children("office"), vec!["terminal"]);
+    assert_eq!(
+        config.get_profile("terminal").unwrap().extends.as_deref(),
+        Some("office")
+    );
+}
+
+const OS_CONFIG: &str = r#"
+[options]
+projects_directory = "/home/user/Projects"
+
+[options.windows]
+projects_directory = 'D:\Projects'
+
+[profiles.default]
+editor = "code"
+editor_args = ["."]
+shell = "bash"
+env = { RUST_LOG = "info" }
+
+[profiles.default.windows]
+editor = "code.cmd"
+shell = "pwsh"
+env = { HOME_DRIVE = "D:" }
+
+[profiles.default.macos]
+shell = "zsh"
+
+[profiles.work]
+extends = "default"
+editor_fork_mode = true
+"#;
+
+#[test]
+fn test_config_os_sections() {
+    let linux = parse_config_for_os(OS_CONFIG, "linux").unwrap();
+    let default = linux.get_profile("default").unwrap();
+    assert_eq!(default.editor, "code".into());
+    assert_eq!(default.shell, "bash");
+    assert_eq!(
+        linux.options.projects_directory,
+        PathBuf::from("/home/user/Projects")
+    );
+
+    let windows = parse_config_for_os(OS_CONFIG, "windows").unwrap();
+    let default = windows.get_profile("default").unwrap();
+    assert_eq!(default.editor, "code.cmd".into());
+    assert_eq!(default.editor_args, vec![".".to_string()]);
+    assert_eq!(default.shell, "pwsh");
+    assert_eq!(default.env["RUST_LOG"], "info");
+    assert_eq!(default.env["HOME_DRIVE"], "D:");
+    assert_eq!(
+        windows.options.projects_directory,
+        PathBuf::from(r"D:\Projects")
+    );
+
+    let work = windows.get_profile("work").unwrap();
+    assert_eq!(work.editor, "code.cmd".into());
+    assert!(work.editor_fork_mode);
+    assert_eq!(windows.field_origin("work", "shell"), "default.windows");
+
+    let macos = parse_config_for_os(OS_CONFIG, "macos").unwrap();
+    assert_eq!(macos.get_profile("work").unwrap().shell, "zsh");
+}
+
+#[test]
+fn test_config_os_sections_save() {
+    let expected: toml::Table = toml::from_str(OS_CONFIG).unwrap();
+    let mut config = parse_config_for_os(OS_CONFIG, "windows").unwrap();
+    assert_eq!(config.to_table().unwrap(), expected);
+
+    // Values from the section of the current OS are changed in that section.
+    let default = config.profiles.get_mut("default").unwrap();
+    default.set_field("shell", parse_value("nu")).unwrap();
+    default.set_field("editor_args", parse_value("[]")).unwrap();
+    let saved = config.to_table().unwrap();
+    let default = saved["profiles"]["default"].as_table().unwrap();
+    assert_eq!(default["shell"].as_str(), Some("bash"));
+    assert_eq!(default["windows"]["shell"].as_str(), Some("nu"));
+    assert_eq!(default["editor_args"].as_array().map(Vec::len), Some(0));
+    assert_eq!(saved["options"], expected["options"]);
+}
+
+#[test]
+fn test_config_os_sections_unknown_field() {
+    let content = OS_CONFIG.replace("[profiles.default.macos]", "[profiles.default.freebsd]");
+    assert!(parse_config(&content).is_err());
+
+    let content = OS_CONFIG.replace("shell = \"pwsh\"", "shel = \"pwsh\"");
+    assert!(parse_config(&content).is_err());
+}
+
+#[test]
+fn test_config_default_is_static() {
+    // Loading a file without profiles uses the platform defaults instead of searching `PATH`.
+    let config: Config = toml::from_str("[options]\ndisplay_hidden = true").unwrap();
+    let profile = config.get_profile("default").unwrap();
+    assert_eq!(profile.shell, platform::fallback_shell());
+    assert_eq!(profile.editor.as_slice(), [platform::fallback_editor()]);
+}
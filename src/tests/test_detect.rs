@@ -0,0 +1,111 @@
+use std::{ffi::OsString, fs, os::unix::fs::PermissionsExt, path::Path};
+
+use crate::{
+    config::{ConfigError, ProgramCandidates},
+    detect::{detect_editors, detect_shells},
+    editor::EditorPresets,
+    platform::find_program,
+    program::{LaunchOptions, ProgramError, check_program},
+    tests::TestContext,
+};
+
+fn create_program(dir: &Path, name: &str, executable: bool) {
+    let path = dir.join(name);
+    fs::write(&path, "#!/bin/sh\n").unwrap();
+    let mode = if executable { 0o755 } else { 0o644 };
+    fs::set_permissions(&path, fs::Permissions::from_mode(mode)).unwrap();
+}
+
+fn path_var(dirs: &[&Path]) -> OsString {
+    std::env::join_paths(dirs).unwrap()
+}
+
+#[test]
+fn test_find_program() {
+    let first = TestContext::setup();
+    let second = TestContext::setup();
+    create_program(first.path(), "nvim", false);
+    create_program(second.path(), "nvim", true);
+    create_program(second.path(), "zsh", true);
+
+    let path_var = path_var(&[first.path(), second.path()]);
+    assert_eq!(
+        find_program("nvim", &path_var),
+        Some(second.path().join("nvim"))
+    );
+    assert_eq!(find_program("hx", &path_var), None);
+}
+
+#[test]
+fn test_check_program() {
+    let context = TestContext::setup();
+    let bin = context.path().join("bin");
+    fs::create_dir(&bin).unwrap();
+    create_program(&bin, "nvim", true);
+
+    let options = |program| LaunchOptions {
+        program,
+        cwd: Some(context.path()),
+        env: Some(vec![(
+            "PATH".to_string(),
+            bin.to_string_lossy().to_string(),
+        )]),
+        ..Default::default()
+    };
+    assert!(check_program(&options("nvim")).is_ok());
+    assert!(check_program(&options("bin/nvim")).is_ok());
+    assert!(matches!(
+        check_program(&options("hx")),
+        Err(ProgramError::ProgramNotFound(program)) if program == "hx"
+    ));
+    assert!(check_program(&options("bin/hx")).is_err());
+}
+
+#[test]
+fn test_detect_editors() {
+    let context = TestContext::setup();
+    create_program(context.path(), "nvim", true);
+    create_program(context.path(), "code", true);
+    create_program(context.path(), "notepad", true);
+
+    let editors = detect_editors(&EditorPresets::builtin(), &path_var(&[context.path()]));
+    let found: Vec<(&str, &str)> = editors
+        .iter()
+        .map(|editor| (editor.program.as_str(), editor.preset.as_str()))
+        .collect();
+
+    assert_eq!(found, vec![("code", "vscode"), ("nvim", "neovim")]);
+}
+
+#[test]
+fn test_detect_shells() {
+    let context = TestContext::setup();
+    create_program(context.path(), "fish", true);
+    create_program(context.path(), "bash", true);
+
+    let shells = detect_shells(&path_var(&[context.path()]));
+    let found: Vec<&str> = shells.iter().map(|shell| shell.program.as_str()).collect();
+
+    assert_eq!(found, vec!["bash", "fish"]);
+}
+
+#[test]
+fn test_program_candidates() {
+    let context = TestContext::setup();
+    create_program(context.path(), "code", true);
+    create_program(context.path(), "nvim", true);
+    let path_var = path_var(&[context.path()]);
+
+    let editors = ProgramCandidates::List(vec!["cursor".into(), "code".into(), "nvim".into()]);
+    assert_eq!(editors.find(&path_var).unwrap(), "code");
+
+    // A single program is used even if it is not in PATH.
+    let editor = ProgramCandidates::from("cursor");
+    assert_eq!(editor.find(&path_var).unwrap(), "cursor");
+
+    let missing = ProgramCandidates::List(vec!["cursor".into(), "zed".into()]);
+    assert!(matches!(
+        missing.find(&path_var),
+        Err(ConfigError::ProgramsNotFound(programs)) if programs == "cursor, zed"
+    ));
+}
@@ -0,0 +1,153 @@
+use std::fs;
+
+use crate::{
+    config::{Config, parse_value_for},
+    document::{ConfigDocument, parse_key},
+    tests::TestContext,
+};
+
+const CONFIG: &str = r#"# Kanri configuration
+version = "3"
+
+[options]
+# Where projects live.
+projects_directory = "/home/user/Projects"
+display_hidden = false # Show dotfiles.
+
+[profiles.default]
+editor = "nvim"
+shell = "bash"
+env = { RUST_LOG = "info" }
+"#;
+
+fn keys(key: &str) -> Vec<String> {
+    parse_key(key).unwrap()
+}
+
+#[test]
+fn test_document_parse_key() {
+    assert_eq!(
+        keys("options.display_hidden"),
+        ["options", "display_hidden"]
+    );
+    assert_eq!(
+        keys(r#"profiles."my.profile".editor"#),
+        ["profiles", "my.profile", "editor"]
+    );
+    assert!(parse_key("options..display_hidden").is_err());
+}
+
+#[test]
+fn test_document_set_keeps_comments() {
+    let mut document = ConfigDocument::parse(CONFIG).unwrap();
+    document
+        .set(&keys("options.display_hidden"), &true.into())
+        .unwrap();
+    document
+        .set(
+            &keys("profiles.default.editor"),
+            &parse_value_for("code", None),
+        )
+        .unwrap();
+
+    assert_eq!(
+        document.to_string(),
+        CONFIG
+            .replace("display_hidden = false", "display_hidden = true")
+            .replace(r#"editor = "nvim""#, r#"editor = "code""#)
+    );
+}
+
+#[test]
+fn test_document_set_new_values() {
+    let mut document = ConfigDocument::parse(CONFIG).unwrap();
+    document
+        .set(&keys("profiles.default.env.EDITOR"), &"nvim".into())
+        .unwrap();
+    document
+        .set(&keys("recent.enabled"), &false.into())
+        .unwrap();
+
+    let value = document.to_value().unwrap();
+    assert_eq!(
+        value["profiles"]["default"]["env"]["RUST_LOG"],
+        "info".into()
+    );
+    assert_eq!(value["profiles"]["default"]["env"]["EDITOR"], "nvim".into());
+    assert_eq!(value["recent"]["enabled"], false.into());
+    assert!(
+        document
+            .set(&keys("profiles.default.editor.name"), &"code".into())
+            .is_err()
+    );
+}
+
+#[test]
+fn test_document_unset() {
+    let mut document = ConfigDocument::parse(CONFIG).unwrap();
+    assert!(
+        document
+            .unset(&keys("profiles.default.env.RUST_LOG"))
+            .unwrap()
+    );
+    assert!(document.unset(&keys("options.display_hidden")).unwrap());
+    assert!(!document.unset(&keys("options.display_hidden")).unwrap());
+    assert!(!document.unset(&keys("recent.enabled")).unwrap());
+
+    let config = Config::from_value(document.to_value().unwrap()).unwrap();
+    assert!(!config.options.display_hidden);
+    assert!(config.get_profile("default").unwrap().env.is_empty());
+    assert!(document.to_string().contains("# Where projects live."));
+}
+
+#[test]
+fn test_document_parse_value_for() {
+    let string = toml::Value::String(String::new());
+    assert_eq!(parse_value_for("123", Some(&string)), "123".into());
+    assert_eq!(parse_value_for("true", Some(&string)), "true".into());
+    assert_eq!(
+        parse_value_for(r#"["code", "nvim"]"#, Some(&string)),
+        toml::Value::Array(vec!["code".into(), "nvim".into()])
+    );
+    assert_eq!(parse_value_for("false", Some(&true.into())), false.into());
+    assert_eq!(parse_value_for("3", None), 3.into());
+}
+
+#[test]
+fn test_document_sync() {
+    let mut document = ConfigDocument::parse(CONFIG).unwrap();
+    let mut table: toml::Table = CONFIG.parse().unwrap();
+    table["options"]["display_hidden"] = true.into();
+    table["profiles"]["default"]
+        .as_table_mut()
+        .unwrap()
+        .remove("shell");
+    table["profiles"]
+        .as_table_mut()
+        .unwrap()
+        .insert("work".to_string(), toml::toml! { editor = "code" }.into());
+    document.sync(&table);
+
+    assert_eq!(
+        document.to_string(),
+        CONFIG
+            .replace("display_hidden = false", "display_hidden = true")
+            .replace("shell = \"bash\"\n", "")
+            + "\n[profiles.work]\neditor = \"code\"\n"
+    );
+}
+
+#[test]
+fn test_document_config_save() {
+    let context = TestContext::setup();
+    let path = context.path().join("config.toml");
+    fs::write(&path, CONFIG).unwrap();
+
+    let state_path = context.path().join("state.json");
+    let mut config = Config::load_with_layers(&path, &state_path, Vec::new()).unwrap();
+    config.recent.enabled = false;
+    config.save(&path).unwrap();
+
+    let content = fs::read_to_string(&path).unwrap();
+    assert_eq!(content, format!("{CONFIG}\n[recent]\nenabled = false\n"));
+}
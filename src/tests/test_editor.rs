@@ -0,0 +1,182 @@
+use std::path::{Path, PathBuf};
+
+use crate::{
+    config::Config,
+    editor::{ArgValues, EditorPresets, FileTarget, LineSyntax, expand_args},
+};
+
+fn strings(values: &[&str]) -> Vec<String> {
+    values.iter().map(|value| value.to_string()).collect()
+}
+
+#[test]
+fn test_file_target_parse() {
+    assert_eq!(
+        FileTarget::parse("src/main.rs"),
+        FileTarget::new("src/main.rs")
+    );
+
+    let target = FileTarget::parse("src/main.rs:42");
+    assert_eq!(target.file, PathBuf::from("src/main.rs"));
+    assert_eq!(target.line, Some(42));
+    assert_eq!(target.column, None);
+
+    let target = FileTarget::parse("src/main.rs:42:7");
+    assert_eq!(target.file, PathBuf::from("src/main.rs"));
+    assert_eq!(target.line, Some(42));
+    assert_eq!(target.column, Some(7));
+
+    let target = FileTarget::parse("C:\\src\\main.rs:3");
+    assert_eq!(target.file, PathBuf::from("C:\\src\\main.rs"));
+    assert_eq!(target.line, Some(3));
+
+    let target = FileTarget::parse("notes:todo.md");
+    assert_eq!(target.file, PathBuf::from("notes:todo.md"));
+    assert_eq!(target.line, None);
+}
+
+#[test]
+fn test_editor_presets_find() {
+    let presets = EditorPresets::builtin();
+    let preset_name = |program: &str| presets.find(program).map(|(name, _)| name);
+
+    assert_eq!(preset_name("code"), Some("vscode"));
+    assert_eq!(preset_name("codium.cmd"), Some("vscode"));
+    assert_eq!(preset_name("/usr/bin/nvim"), Some("neovim"));
+    assert_eq!(preset_name("IDEA64.exe"), Some("jetbrains"));
+    assert_eq!(preset_name("unknown"), None);
+
+    assert_eq!(presets.line_syntax("emacsclient"), LineSyntax::PlusColumn);
+    assert_eq!(presets.line_syntax("hx"), LineSyntax::Colon);
+    assert_eq!(presets.line_syntax("unknown"), LineSyntax::FileOnly);
+
+    let (_, vscode) = presets.find("cursor").unwrap();
+    assert_eq!(vscode.args, vec![".".to_string()]);
+    assert!(vscode.fork_mode);
+
+    // gedit is a GUI editor, so it doesn't block the terminal.
+    let (name, gedit) = presets.find("gedit").unwrap();
+    assert_eq!(name, "gedit");
+    assert!(gedit.fork_mode);
+    assert_eq!(gedit.line_syntax, LineSyntax::Plus);
+    let (_, nano) = presets.find("nano").unwrap();
+    assert!(!nano.fork_mode);
+}
+
+#[test]
+fn test_editor_presets_custom() {
+    let config: Config = toml::from_str(
+        r#"
+[editors.fleet]
+programs = ["fleet"]
+args = ["{path}"]
+fork_mode = true
+line_syntax = "colon"
+
+[editors.vim]
+programs = ["vim"]
+line_syntax = "file_only"
+"#,
+    )
+    .unwrap();
+    let presets = config.editor_presets();
+
+    let (name, fleet) = presets.find("fleet").unwrap();
+    assert_eq!(name, "fleet");
+    assert_eq!(fleet.args, vec!["{path}".to_string()]);
+    assert_eq!(fleet.line_syntax, LineSyntax::Colon);
+
+    // Custom presets replace built-in ones with the same name.
+    assert_eq!(presets.line_syntax("vim"), LineSyntax::FileOnly);
+    assert_eq!(presets.find("gvim"), None);
+    assert_eq!(presets.line_syntax("nvim"), LineSyntax::Plus);
+}
+
+#[test]
+fn test_line_syntax_args() {
+    let target = FileTarget::parse("main.rs:42:7");
+
+    assert_eq!(
+        LineSyntax::Goto.args(&target),
+        strings(&["-g", "main.rs:42:7"])
+    );
+    assert_eq!(LineSyntax::Colon.args(&target), strings(&["main.rs:42:7"]));
+    assert_eq!(LineSyntax::Plus.args(&target), strings(&["+42", "main.rs"]));
+    assert_eq!(
+        LineSyntax::PlusColumn.args(&target),
+        strings(&["+42:7", "main.rs"])
+    );
+    assert_eq!(
+        LineSyntax::LineFlag.args(&target),
+        strings(&["--line", "42", "--column", "7", "main.rs"])
+    );
+    assert_eq!(
+        LineSyntax::ShortFlags.args(&target),
+        strings(&["-l", "42", "-c", "7", "main.rs"])
+    );
+    assert_eq!(LineSyntax::FileOnly.args(&target), strings(&["main.rs"]));
+
+    // Without a line, every editor gets just the file.
+    let target = FileTarget::new("main.rs");
+    assert_eq!(LineSyntax::Goto.args(&target), strings(&["main.rs"]));
+}
+
+#[test]
+fn test_expand_args_placeholders() {
+    let target = FileTarget::parse("/projects/app/main.rs:42");
+    let values = ArgValues {
+        path: Path::new("/projects/app"),
+        name: "app",
+        target: Some(&target),
+    };
+
+    let args = strings(&[
+        "--title",
+        "{name}",
+        "{path}",
+        "--goto={file}:{line}:{column}",
+    ]);
+    assert_eq!(
+        expand_args(&args, &values, LineSyntax::Goto),
+        strings(&[
+            "--title",
+            "app",
+            "/projects/app",
+            "--goto=/projects/app/main.rs:42:1"
+        ])
+    );
+}
+
+#[test]
+fn test_expand_args_without_target() {
+    let values = ArgValues {
+        path: Path::new("/projects/app"),
+        name: "app",
+        target: None,
+    };
+
+    let args = strings(&["{path}", "--goto={file}:{line}"]);
+    assert_eq!(
+        expand_args(&args, &values, LineSyntax::Goto),
+        strings(&["/projects/app"])
+    );
+}
+
+#[test]
+fn test_expand_args_appends_target() {
+    let target = FileTarget::parse("main.rs:42");
+    let values = ArgValues {
+        path: Path::new("/projects/app"),
+        name: "app",
+        target: Some(&target),
+    };
+
+    assert_eq!(
+        expand_args(&strings(&["."]), &values, LineSyntax::Goto),
+        strings(&[".", "-g", "main.rs:42"])
+    );
+    assert_eq!(
+        expand_args(&[], &values, LineSyntax::Plus),
+        strings(&["+42", "main.rs"])
+    );
+}
@@ -0,0 +1,155 @@
+use std::{fs, io::ErrorKind, thread};
+
+use crate::{
+    config::Config,
+    files::{FileLock, with_suffix, write_atomic},
+    state::State,
+    tests::TestContext,
+};
+
+const CONFIG: &str = r#"version = "3"
+
+# Profiles are copied from this one.
+[profiles.default]
+editor = "nvim"
+shell = "bash"
+"#;
+
+#[test]
+fn test_files_write_atomic_backup() {
+    let context = TestContext::setup();
+    let path = context.path().join("config.toml");
+    let backup = with_suffix(&path, "bak");
+    assert_eq!(backup, context.path().join("config.toml.bak"));
+
+    write_atomic(&path, b"first", true).unwrap();
+    assert_eq!(fs::read_to_string(&path).unwrap(), "first");
+    assert!(!backup.exists());
+
+    write_atomic(&path, b"second", true).unwrap();
+    assert_eq!(fs::read_to_string(&path).unwrap(), "second");
+    assert_eq!(fs::read_to_string(&backup).unwrap(), "first");
+
+    // No temporary files are left behind.
+    assert_eq!(fs::read_dir(context.path()).unwrap().count(), 2);
+}
+
+#[test]
+fn test_files_write_atomic_read_only() {
+    let context = TestContext::setup();
+    let path = context.path().join("config.toml");
+    fs::write(&path, "managed").unwrap();
+    let mut permissions = fs::metadata(&path).unwrap().permissions();
+    permissions.set_readonly(true);
+    fs::set_permissions(&path, permissions).unwrap();
+
+    let error = write_atomic(&path, b"changed", true).unwrap_err();
+    assert_eq!(error.kind(), ErrorKind::PermissionDenied);
+    assert_eq!(fs::read_to_string(&path).unwrap(), "managed");
+}
+
+#[cfg(unix)]
+#[test]
+fn test_files_write_atomic_unix() {
+    use std::os::unix::fs::{PermissionsExt, symlink};
+
+    let context = TestContext::setup();
+    let target = context.path().join("dotfiles/config.toml");
+    let link = context.path().join("config.toml");
+    fs::create_dir(context.path().join("dotfiles")).unwrap();
+    fs::write(&target, "first").unwrap();
+    fs::set_permissions(&target, fs::Permissions::from_mode(0o640)).unwrap();
+    symlink(&target, &link).unwrap();
+
+    write_atomic(&link, b"second", true).unwrap();
+    assert!(fs::symlink_metadata(&link).unwrap().is_symlink());
+    assert_eq!(fs::read_to_string(&target).unwrap(), "second");
+    assert_eq!(
+        fs::metadata(&target).unwrap().permissions().mode() & 0o777,
+        0o640
+    );
+    assert!(with_suffix(&target, "bak").exists());
+
+    // New files get the same permissions as with `fs::write`.
+    let new = context.path().join("state.json");
+    write_atomic(&new, b"{}", true).unwrap();
+    fs::write(context.path().join("expected"), "{}").unwrap();
+    assert_eq!(
+        fs::metadata(&new).unwrap().permissions().mode(),
+        fs::metadata(context.path().join("expected"))
+            .unwrap()
+            .permissions()
+            .mode()
+    );
+}
+
+#[test]
+fn test_files_lock_is_exclusive() {
+    let context = TestContext::setup();
+    let path = context.path().join("state.json");
+    let lock = FileLock::acquire(&path).unwrap();
+
+    let other = fs::File::open(with_suffix(&path, "lock")).unwrap();
+    assert!(other.try_lock().is_err());
+    drop(lock);
+    assert!(other.try_lock().is_ok());
+}
+
+#[test]
+fn test_files_try_lock() {
+    let context = TestContext::setup();
+    let path = context.path().join("config.toml");
+    let lock = FileLock::try_acquire(&path).unwrap();
+    assert!(lock.is_some());
+    assert!(FileLock::try_acquire(&path).unwrap().is_none());
+
+    drop(lock);
+    assert!(FileLock::try_acquire(&path).unwrap().is_some());
+}
+
+#[test]
+fn test_files_concurrent_config_writers() {
+    let context = TestContext::setup();
+    let path = context.path().join("config.toml");
+    let state_path = context.path().join("state.json");
+    fs::write(&path, CONFIG).unwrap();
+
+    thread::scope(|scope| {
+        for i in 0..8 {
+            let (path, state_path) = (&path, &state_path);
+            scope.spawn(move || {
+                let _lock = Config::lock(path).unwrap();
+                let mut config = Config::load_with_layers(path, state_path, Vec::new()).unwrap();
+                config.copy_profile("default", &format!("copy{i}")).unwrap();
+                config.save(path).unwrap();
+            });
+        }
+    });
+
+    let config = Config::load_with_layers(&path, &state_path, Vec::new()).unwrap();
+    for i in 0..8 {
+        assert!(config.is_profile_exist(&format!("copy{i}")));
+    }
+    let content = fs::read_to_string(&path).unwrap();
+    assert!(content.contains("# Profiles are copied from this one."));
+}
+
+#[test]
+fn test_files_concurrent_state_writers() {
+    let context = TestContext::setup();
+    let path = context.path().join("state/state.json");
+
+    thread::scope(|scope| {
+        for _ in 0..16 {
+            scope.spawn(|| {
+                let _lock = State::lock(&path).unwrap();
+                let mut state = State::load(&path).unwrap();
+                state.record_open("alpha");
+                state.save(&path).unwrap();
+            });
+        }
+    });
+
+    let state = State::load(&path).unwrap();
+    assert_eq!(state.history["alpha"].open_count, 16);
+}
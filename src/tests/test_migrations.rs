@@ -0,0 +1,146 @@
+use std::fs;
+
+use toml_edit::DocumentMut;
+
+use crate::{config::Config, migrations::migrate_config, state::State, tests::TestContext};
+
+const V1_CONFIG: &str = r#"# Kanri configuration
+[options]
+current_profile = "default"
+
+[profiles.default]
+editor = "nvim" # The best editor.
+shell = "bash"
+shell_args = ["-l"]
+"#;
+
+const V2_CONFIG: &str = r#"version = "2"
+
+[recent]
+enabled = true # Remember the last project.
+recent_project = "alpha"
+"#;
+
+fn migrate(content: &str) -> (DocumentMut, State, bool) {
+    let mut document: DocumentMut = content.parse().unwrap();
+    let mut state = State::default();
+    let was_migrated = migrate_config(&mut document, &mut state).unwrap();
+    (document, state, was_migrated)
+}
+
+#[test]
+fn test_migrations_v1_keeps_comments() {
+    let (document, _, was_migrated) = migrate(V1_CONFIG);
+    assert!(was_migrated);
+
+    assert_eq!(
+        document.to_string(),
+        format!(
+            "version = \"3\"\n\n{}",
+            V1_CONFIG.replace("shell_args = [\"-l\"]\n", "")
+        )
+    );
+}
+
+#[test]
+fn test_migrations_v2_moves_recent_project() {
+    let (document, state, was_migrated) = migrate(V2_CONFIG);
+    assert!(was_migrated);
+    assert_eq!(state.recent_project, "alpha");
+    assert_eq!(
+        document.to_string(),
+        "version = \"3\"\n\n[recent]\nenabled = true # Remember the last project.\n"
+    );
+
+    // A recent project in the state is newer than the one in the file.
+    let mut document: DocumentMut = V2_CONFIG.parse().unwrap();
+    let mut state = State {
+        recent_project: "beta".to_string(),
+        ..Default::default()
+    };
+    migrate_config(&mut document, &mut state).unwrap();
+    assert_eq!(state.recent_project, "beta");
+
+    // The section is removed if nothing is left in it.
+    let (document, state, _) = migrate("version = \"2\"\n\n[recent]\nrecent_project = \"\"\n");
+    assert!(state.recent_project.is_empty());
+    assert_eq!(document.to_string(), "version = \"3\"\n");
+}
+
+#[test]
+fn test_migrations_current_version() {
+    let content = format!("version = \"3\" # Do not change.\n{V1_CONFIG}");
+    let (document, _, was_migrated) = migrate(&content);
+    assert!(!was_migrated);
+    assert_eq!(document.to_string(), content);
+}
+
+#[test]
+fn test_migrations_unknown_version() {
+    let mut document: DocumentMut = "version = \"99\"".parse().unwrap();
+    assert!(migrate_config(&mut document, &mut State::default()).is_err());
+}
+
+#[test]
+fn test_migrations_load_saves_state() {
+    let context = TestContext::setup();
+    let path = context.path().join("config.toml");
+    let state_path = context.path().join("state/state.json");
+    fs::write(&path, V2_CONFIG).unwrap();
+
+    let config = Config::load_with_layers(&path, &state_path, Vec::new()).unwrap();
+    assert!(config.recent.enabled);
+    assert_eq!(State::load(&state_path).unwrap().recent_project, "alpha");
+    assert!(
+        !fs::read_to_string(&path)
+            .unwrap()
+            .contains("recent_project")
+    );
+}
+
+#[test]
+fn test_migrations_load_while_locked() {
+    let context = TestContext::setup();
+    let path = context.path().join("config.toml");
+    let state_path = context.path().join("state/state.json");
+    fs::write(&path, V2_CONFIG).unwrap();
+
+    // A command that holds the lock saves the migrated file itself.
+    let lock = Config::lock(&path).unwrap();
+    let config = Config::load_with_layers(&path, &state_path, Vec::new()).unwrap();
+    assert_eq!(fs::read_to_string(&path).unwrap(), V2_CONFIG);
+    assert_eq!(State::load(&state_path).unwrap().recent_project, "alpha");
+
+    config.save(&path).unwrap();
+    drop(lock);
+    assert!(
+        !fs::read_to_string(&path)
+            .unwrap()
+            .contains("recent_project")
+    );
+}
+
+#[test]
+fn test_migrations_load_read_only() {
+    let context = TestContext::setup();
+    let path = context.path().join("config.toml");
+    let state_path = context.path().join("state/state.json");
+    fs::write(&path, V2_CONFIG).unwrap();
+    let mut permissions = fs::metadata(&path).unwrap().permissions();
+    permissions.set_readonly(true);
+    fs::set_permissions(&path, permissions).unwrap();
+
+    // The file is migrated in memory.
+    let config = Config::load_with_layers(&path, &state_path, Vec::new()).unwrap();
+    assert!(config.recent.enabled);
+    assert_eq!(config.version, "3");
+    assert_eq!(fs::read_to_string(&path).unwrap(), V2_CONFIG);
+    assert_eq!(State::load(&state_path).unwrap().recent_project, "alpha");
+
+    // Loading it again doesn't bring back the old recent project.
+    let mut state = State::load(&state_path).unwrap();
+    state.recent_project = "beta".to_string();
+    state.save(&state_path).unwrap();
+    Config::load_with_layers(&path, &state_path, Vec::new()).unwrap();
+    assert_eq!(State::load(&state_path).unwrap().recent_project, "beta");
+}
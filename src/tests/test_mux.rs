@@ -0,0 +1,252 @@
+use std::{path::Path, process::Command};
+
+use crate::{
+    mux::{MuxLayout, MuxSession, MuxWindow, Tmux, create_commands, session_name, zellij_args},
+    project::ProjectMeta,
+    tests::TestContext,
+};
+
+fn layout() -> MuxLayout {
+    MuxLayout {
+        windows: vec![
+            MuxWindow {
+                name: Some("editor".to_string()),
+                panes: vec!["echo edit".to_string()],
+                ..Default::default()
+            },
+            MuxWindow {
+                name: Some("server".to_string()),
+                cwd: Some("web".into()),
+                panes: vec!["echo serve".to_string(), "echo test".to_string()],
+                layout: Some("even-horizontal".to_string()),
+            },
+        ],
+        zellij_layout: None,
+    }
+}
+
+fn strings(args: &[&str]) -> Vec<String> {
+    args.iter().map(|arg| arg.to_string()).collect()
+}
+
+#[test]
+fn test_mux_session_name() {
+    assert_eq!(session_name("kanri"), "kanri");
+    assert_eq!(session_name("site.com:8080"), "site_com_8080");
+}
+
+#[test]
+fn test_mux_default_commands() {
+    let layout = MuxLayout::default();
+    let env = vec![("KANRI_PROJECT_NAME".to_string(), "alpha".to_string())];
+    let session = MuxSession {
+        name: "alpha",
+        path: Path::new("/projects/alpha"),
+        shell: "",
+        env: &env,
+        layout: &layout,
+    };
+
+    assert_eq!(
+        create_commands(&session),
+        vec![
+            strings(&[
+                "new-session",
+                "-d",
+                "-s",
+                "alpha",
+                "-c",
+                "/projects/alpha",
+                "-e",
+                "KANRI_PROJECT_NAME=alpha",
+            ]),
+            strings(&[
+                "set-environment",
+                "-t",
+                "=alpha",
+                "KANRI_PROJECT_NAME",
+                "alpha"
+            ]),
+        ]
+    );
+}
+
+#[test]
+fn test_mux_layout_commands() {
+    let layout = layout();
+    let session = MuxSession {
+        name: "alpha",
+        path: Path::new("/projects/alpha"),
+        shell: "zsh",
+        env: &[],
+        layout: &layout,
+    };
+
+    assert_eq!(
+        create_commands(&session),
+        vec![
+            strings(&[
+                "new-session",
+                "-d",
+                "-s",
+                "alpha",
+                "-c",
+                "/projects/alpha",
+                "-n",
+                "editor",
+                "zsh",
+            ]),
+            strings(&["send-keys", "-t", "=alpha:{end}", "echo edit", "Enter"]),
+            strings(&[
+                "new-window",
+                "-t",
+                "=alpha:",
+                "-c",
+                "/projects/alpha/web",
+                "-n",
+                "server",
+                "zsh",
+            ]),
+            strings(&["send-keys", "-t", "=alpha:{end}", "echo serve", "Enter"]),
+            strings(&[
+                "split-window",
+                "-t",
+                "=alpha:{end}",
+                "-c",
+                "/projects/alpha/web",
+                "zsh",
+            ]),
+            strings(&["send-keys", "-t", "=alpha:{end}", "echo test", "Enter"]),
+            strings(&["select-layout", "-t", "=alpha:{end}", "even-horizontal"]),
+            strings(&["select-window", "-t", "=alpha:{start}"]),
+        ]
+    );
+}
+
+#[test]
+fn test_mux_attach_args() {
+    let tmux = Tmux::new();
+    assert_eq!(
+        tmux.attach_args("alpha", false),
+        strings(&["attach-session", "-t", "=alpha"])
+    );
+    assert_eq!(
+        tmux.attach_args("alpha", true),
+        strings(&["switch-client", "-t", "=alpha"])
+    );
+    assert_eq!(
+        Tmux::with_socket("test").attach_args("alpha", false),
+        strings(&["-L", "test", "attach-session", "-t", "=alpha"])
+    );
+}
+
+#[test]
+fn test_mux_zellij_args() {
+    let layout = Path::new("/projects/alpha/layout.kdl");
+    assert_eq!(
+        zellij_args("alpha", None, false),
+        strings(&["attach", "--create", "alpha"])
+    );
+    assert_eq!(
+        zellij_args("alpha", Some(layout), false),
+        strings(&[
+            "--session",
+            "alpha",
+            "--layout",
+            "/projects/alpha/layout.kdl"
+        ])
+    );
+    assert_eq!(
+        zellij_args("alpha", Some(layout), true),
+        strings(&["attach", "--create", "alpha"])
+    );
+}
+
+#[test]
+fn test_mux_layout_from_metadata() {
+    let meta: ProjectMeta = toml::from_str(
+        r#"
+[[mux.windows]]
+name = "editor"
+panes = ["echo edit"]
+
+[[mux.windows]]
+name = "server"
+cwd = "web"
+panes = ["echo serve", "echo test"]
+layout = "even-horizontal"
+"#,
+    )
+    .unwrap();
+
+    assert_eq!(meta.mux, layout());
+    assert!(toml::from_str::<ProjectMeta>("[mux]\nwindow = []").is_err());
+}
+
+/// Runs tmux commands on a separate server, which is killed when the test ends.
+struct TmuxServer(Tmux);
+
+impl TmuxServer {
+    fn start() -> Option<Self> {
+        // Skip the test when tmux is not installed.
+        Command::new("tmux").arg("-V").output().ok()?;
+        let socket = format!("kanri-test-{}", std::process::id());
+        Some(Self(Tmux::with_socket(&socket)))
+    }
+
+    fn lines(&self, command: &[&str]) -> Vec<String> {
+        let output = self.0.run(&strings(command)).unwrap();
+        output.lines().map(str::to_string).collect()
+    }
+}
+
+impl Drop for TmuxServer {
+    fn drop(&mut self) {
+        let _ = self.0.run(&strings(&["kill-server"]));
+    }
+}
+
+#[test]
+fn test_mux_tmux_session() {
+    let Some(server) = TmuxServer::start() else {
+        return;
+    };
+    let context = TestContext::setup();
+    // tmux reports resolved paths, and temporary directories may be behind symlinks.
+    let path = context.path().canonicalize().unwrap();
+    std::fs::create_dir(path.join("web")).unwrap();
+    let layout = layout();
+    let env = vec![("KANRI_PROJECT_NAME".to_string(), "alpha".to_string())];
+    let session = MuxSession {
+        name: "alpha",
+        path: &path,
+        shell: "sh",
+        env: &env,
+        layout: &layout,
+    };
+
+    assert!(!server.0.has_session("alpha").unwrap());
+    server.0.create_session(&session).unwrap();
+    assert!(server.0.has_session("alpha").unwrap());
+    // Names are matched exactly, not by prefix.
+    assert!(!server.0.has_session("alp").unwrap());
+
+    assert_eq!(
+        server.lines(&["list-windows", "-t", "=alpha", "-F", "#{window_name}"]),
+        vec!["editor", "server"]
+    );
+    assert_eq!(
+        server.lines(&[
+            "list-panes",
+            "-t",
+            "=alpha:server",
+            "-F",
+            "#{pane_current_path}"
+        ]),
+        vec![path.join("web").to_string_lossy().to_string(); 2]
+    );
+    assert_eq!(
+        server.lines(&["show-environment", "-t", "=alpha", "KANRI_PROJECT_NAME"]),
+        vec!["KANRI_PROJECT_NAME=alpha"]
+    );
+}
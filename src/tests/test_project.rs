@@ -0,0 +1,116 @@
+use std::fs;
+
+use crate::{
+    config::Config,
+    project::{PROJECT_DIR_NAME, ProjectError, ProjectMeta, parse_env_file},
+    tests::TestContext,
+};
+
+fn write_metadata(context: &TestContext, content: &str) {
+    fs::create_dir_all(context.path().join(PROJECT_DIR_NAME)).unwrap();
+    fs::write(ProjectMeta::path(context.path()), content).unwrap();
+}
+
+#[test]
+fn test_project_meta_missing() {
+    let context = TestContext::setup();
+
+    let meta = ProjectMeta::load(context.path()).unwrap();
+    assert!(meta.tags.is_empty());
+    assert!(meta.profile.is_none());
+}
+
+#[test]
+fn test_project_meta_profile() {
+    let context = TestContext::setup();
+    write_metadata(&context, "profile = \"jetbrains\"\ntags = [\"work\"]\n");
+
+    let meta = ProjectMeta::load(context.path()).unwrap();
+    assert_eq!(meta.profile.as_deref(), Some("jetbrains"));
+    assert_eq!(meta.tags, vec!["work".to_string()]);
+}
+
+#[test]
+fn test_project_meta_unknown_field() {
+    let context = TestContext::setup();
+    write_metadata(&context, "editor = \"idea\"\n");
+
+    assert!(matches!(
+        ProjectMeta::load(context.path()),
+        Err(ProjectError::BadMetadata(_))
+    ));
+}
+
+#[test]
+fn test_project_profile_not_found() {
+    let config = Config::default();
+
+    assert!(config.get_profile("default").is_ok());
+    assert!(config.get_profile("jetbrains").is_err());
+}
+
+#[test]
+fn test_parse_env_file() {
+    let content = r#"
+# Database settings
+DATABASE_URL=postgres://localhost/app
+export RUST_LOG=debug # verbose
+GREETING="hello\nworld"
+RAW='$HOME stays as is'
+EMPTY=
+"#;
+
+    let variables = parse_env_file(content, ".env").unwrap();
+    assert_eq!(
+        variables,
+        vec![
+            (
+                "DATABASE_URL".to_string(),
+                "postgres://localhost/app".to_string()
+            ),
+            ("RUST_LOG".to_string(), "debug".to_string()),
+            ("GREETING".to_string(), "hello\nworld".to_string()),
+            ("RAW".to_string(), "$HOME stays as is".to_string()),
+            ("EMPTY".to_string(), String::new()),
+        ]
+    );
+}
+
+#[test]
+fn test_parse_env_file_invalid() {
+    for content in ["NO_VALUE", "1KEY=value", "KEY=\"unterminated"] {
+        assert!(matches!(
+            parse_env_file(content, ".env"),
+            Err(ProjectError::BadEnvFile { line: 1, .. })
+        ));
+    }
+}
+
+#[test]
+fn test_project_env() {
+    let context = TestContext::setup();
+    write_metadata(
+        &context,
+        "env_file = \".env\"\n[env]\nRUST_LOG = \"info\"\nPORT = \"8080\"\n",
+    );
+    fs::write(
+        context.path().join(".env"),
+        "RUST_LOG=debug\nSECRET=token\n",
+    )
+    .unwrap();
+
+    let meta = ProjectMeta::load(context.path()).unwrap();
+    let env = meta.load_env(context.path()).unwrap();
+    assert_eq!(env.get("RUST_LOG").map(String::as_str), Some("info"));
+    assert_eq!(env.get("SECRET").map(String::as_str), Some("token"));
+    assert_eq!(env.get("PORT").map(String::as_str), Some("8080"));
+}
+
+#[test]
+fn test_project_env_file_missing() {
+    let context = TestContext::setup();
+    write_metadata(&context, "env_file = \".env\"\n");
+
+    let meta = ProjectMeta::load(context.path()).unwrap();
+    assert!(meta.load_env(context.path()).unwrap().is_empty());
+}
@@ -0,0 +1,90 @@
+use std::collections::HashMap;
+
+use crate::{
+    session::{Handoff, Session, session_env},
+    tests::TestContext,
+};
+
+fn lookup(vars: &[(&str, &str)]) -> impl Fn(&str) -> Option<String> {
+    let vars: HashMap<String, String> = vars
+        .iter()
+        .map(|(name, value)| (name.to_string(), value.to_string()))
+        .collect();
+    move |name| vars.get(name).cloned()
+}
+
+#[test]
+fn test_session_none() {
+    assert!(Session::from_env(lookup(&[])).is_none());
+    assert!(Session::from_env(lookup(&[("KANRI_SESSION", "")])).is_none());
+}
+
+#[test]
+fn test_session_from_env() {
+    let session = Session::from_env(lookup(&[
+        ("KANRI_SESSION", "1"),
+        ("KANRI_SESSION_DEPTH", "2"),
+        ("KANRI_SESSION_ID", "4242"),
+        ("KANRI_PROJECT_NAME", "alpha"),
+    ]))
+    .unwrap();
+
+    assert_eq!(session.depth, 2);
+    assert_eq!(session.id, Some(4242));
+    assert_eq!(session.project_name.as_deref(), Some("alpha"));
+}
+
+#[test]
+fn test_session_from_older_version() {
+    let session = Session::from_env(lookup(&[("KANRI_SESSION", "1")])).unwrap();
+
+    assert_eq!(session.depth, 1);
+    assert_eq!(session.id, None);
+    assert_eq!(session.project_name, None);
+}
+
+#[test]
+fn test_session_env_depth() {
+    let env = session_env(None, Some(10));
+    assert!(env.contains(&("KANRI_SESSION_DEPTH".to_string(), "1".to_string())));
+    assert!(env.contains(&("KANRI_SESSION_ID".to_string(), "10".to_string())));
+
+    let parent = Session::from_env(lookup(&[
+        ("KANRI_SESSION", "1"),
+        ("KANRI_SESSION_DEPTH", "2"),
+    ]))
+    .unwrap();
+    let env = session_env(Some(&parent), Some(11));
+    assert!(env.contains(&("KANRI_SESSION_DEPTH".to_string(), "3".to_string())));
+}
+
+#[test]
+fn test_session_env_without_id() {
+    let env = session_env(None, None);
+    let session = Session::from_env(|name| {
+        env.iter()
+            .find(|(key, _)| key == name)
+            .map(|(_, value)| value.clone())
+    })
+    .unwrap();
+    assert_eq!(session.depth, 1);
+    assert_eq!(session.id, None);
+}
+
+#[test]
+fn test_session_handoff() {
+    let context = TestContext::setup();
+    let path = Handoff::path(&context.path().join("sessions"), 4242);
+
+    assert_eq!(Handoff::take(&path).unwrap(), None);
+
+    let handoff = Handoff {
+        project_name: "beta".to_string(),
+        profile: Some("jetbrains".to_string()),
+        no_hooks: true,
+    };
+    handoff.save(&path).unwrap();
+
+    assert_eq!(Handoff::take(&path).unwrap(), Some(handoff));
+    assert!(!path.exists());
+}
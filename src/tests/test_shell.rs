@@ -0,0 +1,43 @@
+use std::path::Path;
+
+use crate::shell::{Shell, init_script};
+
+const PROJECTS_DIRECTORY: &str = "/home/user/Projects";
+
+#[test]
+fn test_init_bash() {
+    insta::assert_snapshot!(init_script(Shell::Bash, Path::new(PROJECTS_DIRECTORY)));
+}
+
+#[test]
+fn test_init_zsh() {
+    insta::assert_snapshot!(init_script(Shell::Zsh, Path::new(PROJECTS_DIRECTORY)));
+}
+
+#[test]
+fn test_init_fish() {
+    insta::assert_snapshot!(init_script(Shell::Fish, Path::new(PROJECTS_DIRECTORY)));
+}
+
+#[test]
+fn test_init_powershell() {
+    insta::assert_snapshot!(init_script(
+        Shell::Powershell,
+        Path::new(PROJECTS_DIRECTORY)
+    ));
+}
+
+#[test]
+fn test_init_nushell() {
+    insta::assert_snapshot!(init_script(Shell::Nushell, Path::new(PROJECTS_DIRECTORY)));
+}
+
+#[test]
+fn test_init_quotes_projects_directory() {
+    let path = Path::new("/home/o'neil/Projects/");
+
+    assert!(init_script(Shell::Bash, path).contains(r"local base='/home/o'\''neil/Projects'"));
+    assert!(init_script(Shell::Fish, path).contains(r"set -l base '/home/o\'neil/Projects'"));
+    assert!(init_script(Shell::Powershell, path).contains("$base = '/home/o''neil/Projects'"));
+    assert!(init_script(Shell::Nushell, path).contains("let base = r#'/home/o'neil/Projects'#"));
+}
@@ -0,0 +1,97 @@
+use std::path::Path;
+
+use crate::{
+    state::{HistoryEntry, State},
+    tests::TestContext,
+};
+
+fn opened_at(last_opened: u64) -> HistoryEntry {
+    HistoryEntry {
+        last_opened,
+        open_count: 1,
+    }
+}
+
+#[test]
+fn test_state_rank_by_recent() {
+    let mut state = State::default();
+    state.history.insert("old".to_string(), opened_at(10));
+    state.history.insert("new".to_string(), opened_at(20));
+
+    let ranked = state.rank_projects(&["never", "old", "other", "new"]);
+    assert_eq!(ranked, vec!["new", "old", "never", "other"]);
+}
+
+#[test]
+fn test_state_pins() {
+    let mut state = State::default();
+    state.history.insert("new".to_string(), opened_at(20));
+
+    assert!(state.pin("other"));
+    assert!(state.pin("never"));
+    assert!(!state.pin("other"));
+    assert_eq!(state.get_pin(1), Some("other"));
+    assert_eq!(state.get_pin(2), Some("never"));
+    assert_eq!(state.get_pin(0), None);
+    assert_eq!(state.get_pin(3), None);
+
+    let ranked = state.rank_projects(&["never", "old", "other", "new"]);
+    assert_eq!(ranked, vec!["other", "never", "new", "old"]);
+
+    assert!(state.unpin("other"));
+    assert!(!state.unpin("other"));
+    assert_eq!(state.pin_position("never"), Some(1));
+}
+
+#[test]
+fn test_state_record_open() {
+    let mut state = State::default();
+    state.record_open("project");
+    state.record_open("project");
+
+    let entry = state.history.get("project").unwrap();
+    assert_eq!(entry.open_count, 2);
+    assert!(entry.last_opened > 0);
+}
+
+#[test]
+fn test_state_rename_and_remove() {
+    let mut state = State::default();
+    state.history.insert("project".to_string(), opened_at(10));
+    state.pin("project");
+    state.recent_project = "project".to_string();
+    state.trust_hook(Path::new("/projects/project"), "abc");
+
+    state.rename_project(
+        "project",
+        "renamed",
+        Path::new("/projects/project"),
+        Path::new("/projects/renamed"),
+    );
+    assert!(!state.history.contains_key("project"));
+    assert_eq!(state.history.get("renamed"), Some(&opened_at(10)));
+    assert!(state.is_pinned("renamed"));
+    assert_eq!(state.recent_project, "renamed");
+    assert!(!state.is_hook_trusted(Path::new("/projects/project"), "abc"));
+    assert!(state.is_hook_trusted(Path::new("/projects/renamed"), "abc"));
+
+    state.remove_project("renamed");
+    assert!(state.history.is_empty());
+    assert!(state.pins.is_empty());
+    assert!(state.recent_project.is_empty());
+}
+
+#[test]
+fn test_state_save_and_load() {
+    let context = TestContext::setup();
+    let path = context.path().join("state").join("state.json");
+
+    assert!(State::load(&path).unwrap().history.is_empty());
+
+    let mut state = State::default();
+    state.history.insert("project".to_string(), opened_at(10));
+    state.save(&path).unwrap();
+
+    let loaded = State::load(&path).unwrap();
+    assert_eq!(loaded.history.get("project"), Some(&opened_at(10)));
+}